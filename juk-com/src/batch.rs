@@ -0,0 +1,123 @@
+//! The [`Batch`] terminal coalescing the output of [`crate::Interface`].
+
+use alloc::vec::Vec;
+
+use crate::{Style, Terminal};
+
+/// A [`Terminal`] collecting the writes into a buffer, which is written to the wrapped terminal
+/// at once.
+///
+/// The echo of a paste is written character by character, which costs a write per character
+/// without the batching. The buffer is written before reading, flushing and before the other
+/// terminal operations, which are delegated to the wrapped terminal as it may implement them
+/// differently.
+pub struct Batch<'a, T> {
+    terminal: &'a mut T,
+    buf: Vec<u8>,
+}
+
+impl<'a, T: Terminal> Batch<'a, T> {
+    /// Construct a new [`Batch`] wrapping `terminal`, `buf` is reused for the buffer.
+    pub fn new(terminal: &'a mut T, mut buf: Vec<u8>) -> Self {
+        buf.clear();
+        Self { terminal, buf }
+    }
+
+    /// Write the buffered output to the wrapped terminal.
+    pub async fn commit(&mut self) -> Result<(), T::Error> {
+        if self.buf.is_empty() {
+            return Ok(());
+        }
+        let result = self.terminal.write(&self.buf).await;
+        self.buf.clear();
+        result
+    }
+
+    /// Release the buffer for reuse, the buffered output is dropped.
+    pub fn release(self) -> Vec<u8> {
+        self.buf
+    }
+}
+
+impl<'a, T: Terminal> Terminal for Batch<'a, T> {
+    type Error = T::Error;
+
+    async fn read_byte(&mut self) -> Result<u8, Self::Error> {
+        self.commit().await?;
+        self.terminal.read_byte().await
+    }
+
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        self.commit().await?;
+        self.terminal.read(buf).await
+    }
+
+    async fn write(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        self.buf.extend_from_slice(buf);
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        self.commit().await?;
+        self.terminal.flush().await
+    }
+
+    fn ansi(&self) -> bool {
+        self.terminal.ansi()
+    }
+
+    async fn cursor_up(&mut self) -> Result<(), Self::Error> {
+        self.commit().await?;
+        self.terminal.cursor_up().await
+    }
+
+    async fn cursor_left(&mut self) -> Result<(), Self::Error> {
+        self.commit().await?;
+        self.terminal.cursor_left().await
+    }
+
+    async fn cursor_right(&mut self) -> Result<(), Self::Error> {
+        self.commit().await?;
+        self.terminal.cursor_right().await
+    }
+
+    async fn clear_eol(&mut self) -> Result<(), Self::Error> {
+        self.commit().await?;
+        self.terminal.clear_eol().await
+    }
+
+    async fn save_cursor_pos(&mut self) -> Result<(), Self::Error> {
+        self.commit().await?;
+        self.terminal.save_cursor_pos().await
+    }
+
+    async fn restore_cursor_pos(&mut self) -> Result<(), Self::Error> {
+        self.commit().await?;
+        self.terminal.restore_cursor_pos().await
+    }
+
+    async fn bell(&mut self) -> Result<(), Self::Error> {
+        self.commit().await?;
+        self.terminal.bell().await
+    }
+
+    async fn visual_bell(&mut self) -> Result<(), Self::Error> {
+        self.commit().await?;
+        self.terminal.visual_bell().await
+    }
+
+    async fn set_style(&mut self, style: Style) -> Result<(), Self::Error> {
+        self.commit().await?;
+        self.terminal.set_style(style).await
+    }
+
+    async fn reset_style(&mut self) -> Result<(), Self::Error> {
+        self.commit().await?;
+        self.terminal.reset_style().await
+    }
+
+    async fn write_styled(&mut self, style: Style, text: &str) -> Result<(), Self::Error> {
+        self.commit().await?;
+        self.terminal.write_styled(style, text).await
+    }
+}
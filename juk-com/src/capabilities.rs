@@ -0,0 +1,30 @@
+//! Terminal capability probing, see [`crate::Interface::probe()`].
+
+use embassy_time::Duration;
+
+/// The queries sent by the probe.
+///
+/// The bracketed paste mode is queried with DECRQM first, the primary device attributes (DA1)
+/// query goes last. Every ANSI terminal answers DA1 and the answers come in order, so the DA1
+/// answer ends the probe.
+pub(crate) const QUERY: &[u8] = b"\x1b[?2004$p\x1b[c";
+
+/// Time to wait for the answer before the terminal is considered not to support ANSI escape
+/// sequences.
+pub(crate) const TIMEOUT: Duration = Duration::from_millis(500);
+
+/// The DEC private mode number of the bracketed paste mode.
+pub(crate) const BRACKETED_PASTE_MODE: u16 = 2004;
+
+/// The capabilities of the connected terminal, found by [`crate::Interface::probe()`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Capabilities {
+    /// The terminal answered the probe, so it understands ANSI escape sequences (cursor movement,
+    /// SGR styling).
+    pub ansi: bool,
+    /// The terminal reported ANSI color support in its device attributes.
+    pub color: bool,
+    /// The terminal supports the bracketed paste mode.
+    pub bracketed_paste: bool,
+}
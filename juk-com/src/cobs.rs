@@ -0,0 +1,203 @@
+//! Consistent Overhead Byte Stuffing (COBS) framing.
+//!
+//! COBS removes all `0x00` bytes from a message, so that `0x00` can be used as the frame
+//! delimiter (the sentinel). This is the framing used by the binary mode of
+//! [`crate::Interface`], it is exposed here for the other users of the same framing.
+//!
+//! Apart from [`encode`], a convenience wrapper around [`encode_into`], none of the functions
+//! allocate.
+//!
+//! # Usage
+//!
+//! ```
+//! use juk_com::cobs::{self, Decoder};
+//!
+//! let mut encoded = [0; cobs::max_encoded_len(4)];
+//! let len = cobs::encode_into(&[0x11, 0x00, 0x22, 0x33], &mut encoded).unwrap();
+//!
+//! let mut buf = [0; 16];
+//! let mut decoder = Decoder::new(&mut buf);
+//! for &byte in &encoded[..len] {
+//!     assert_eq!(decoder.feed(byte), Ok(None));
+//! }
+//! assert_eq!(decoder.feed(0x00), Ok(Some(&[0x11, 0x00, 0x22, 0x33][..])));
+//! ```
+
+use alloc::{vec, vec::Vec};
+
+/// The frame delimiter.
+pub const SENTINEL: u8 = 0x00;
+
+/// The longest block of non-zero bytes a single code byte can describe.
+const MAX_BLOCK: usize = 254;
+
+/// An error returned by the COBS functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// The output buffer is too small.
+    BufferTooSmall,
+    /// A frame ended in the middle of a block.
+    Truncated,
+}
+
+/// The maximal length of `len` bytes encoded with COBS, excluding the sentinel.
+pub const fn max_encoded_len(len: usize) -> usize {
+    len + len / MAX_BLOCK + 1
+}
+
+/// Encode `src` into `dst`, returns the length of the encoded data.
+///
+/// The sentinel is not appended. `dst` needs to be at least [`max_encoded_len()`] bytes long,
+/// otherwise [`Error::BufferTooSmall`] is returned.
+pub fn encode_into(src: &[u8], dst: &mut [u8]) -> Result<usize, Error> {
+    if dst.len() < max_encoded_len(src.len()) {
+        return Err(Error::BufferTooSmall);
+    }
+
+    let mut code_pos = 0;
+    let mut len = 1;
+    let mut code = 1;
+    for &byte in src {
+        if byte == SENTINEL {
+            dst[code_pos] = code;
+            code_pos = len;
+            len += 1;
+            code = 1;
+            continue;
+        }
+
+        dst[len] = byte;
+        len += 1;
+        code += 1;
+        if code as usize == MAX_BLOCK + 1 {
+            dst[code_pos] = code;
+            code_pos = len;
+            len += 1;
+            code = 1;
+        }
+    }
+    dst[code_pos] = code;
+
+    Ok(len)
+}
+
+/// Encode `src` into a new [`Vec`], the sentinel is not appended.
+pub fn encode(src: &[u8]) -> Vec<u8> {
+    let mut dst = vec![0; max_encoded_len(src.len())];
+    let len = unwrap!(encode_into(src, &mut dst));
+    dst.truncate(len);
+    dst
+}
+
+/// Decode a single frame from `src` into `dst`, returns the length of the decoded data.
+///
+/// `src` should not contain the sentinel, a frame is never longer than the encoded data.
+pub fn decode_into(src: &[u8], dst: &mut [u8]) -> Result<usize, Error> {
+    let mut decoder = Decoder::new(dst);
+    for &byte in src {
+        decoder.feed(byte)?;
+    }
+    Ok(decoder.feed(SENTINEL)?.map_or(0, <[u8]>::len))
+}
+
+/// A streaming COBS decoder writing the decoded frames to a borrowed buffer.
+///
+/// The encoded data is fed byte by byte with [`Decoder::feed()`], a frame is returned when the
+/// sentinel is received. After an error, the rest of the frame is dropped up to the next
+/// sentinel.
+pub struct Decoder<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+    /// Data bytes left in the current block.
+    remaining: u8,
+    /// The code byte of the current block, `0` before the first block.
+    code: u8,
+    /// The last frame was returned, `buf` is reused on the next byte.
+    done: bool,
+    /// An error occured, the input is dropped until the next sentinel.
+    discard: bool,
+}
+
+impl<'a> Decoder<'a> {
+    /// Construct a new [`Decoder`] writing the frames to `buf`.
+    ///
+    /// The frames longer than `buf` are dropped with [`Error::BufferTooSmall`].
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        Self {
+            buf,
+            len: 0,
+            remaining: 0,
+            code: 0,
+            done: false,
+            discard: false,
+        }
+    }
+
+    /// Drop the partially received frame.
+    pub fn reset(&mut self) {
+        self.len = 0;
+        self.remaining = 0;
+        self.code = 0;
+        self.done = false;
+        self.discard = false;
+    }
+
+    /// Feed an encoded byte to the decoder.
+    ///
+    /// Returns the decoded frame when `byte` is the sentinel. A sentinel without any data before
+    /// it is ignored, so repeated sentinels can be used to resynchronize.
+    pub fn feed(&mut self, byte: u8) -> Result<Option<&[u8]>, Error> {
+        if self.done {
+            self.reset();
+        }
+
+        if byte == SENTINEL {
+            let complete = self.remaining == 0;
+            let empty = self.code == 0;
+            if self.discard || empty {
+                self.reset();
+                return Ok(None);
+            }
+
+            self.done = true;
+            return if complete {
+                Ok(Some(&self.buf[..self.len]))
+            } else {
+                Err(Error::Truncated)
+            };
+        }
+
+        if self.discard {
+            return Ok(None);
+        }
+
+        if self.remaining > 0 {
+            self.remaining -= 1;
+            return self.push(byte).map(|()| None);
+        }
+
+        // a code byte, every block except the longest ones is followed by an implicit zero
+        if self.code != 0 && self.code as usize != MAX_BLOCK + 1 {
+            self.push(SENTINEL)?;
+        }
+        self.code = byte;
+        self.remaining = byte - 1;
+        Ok(None)
+    }
+
+    /// Append a decoded byte to the frame.
+    fn push(&mut self, byte: u8) -> Result<(), Error> {
+        match self.buf.get_mut(self.len) {
+            Some(slot) => {
+                *slot = byte;
+                self.len += 1;
+                Ok(())
+            }
+            None => {
+                self.discard = true;
+                Err(Error::BufferTooSmall)
+            }
+        }
+    }
+}
@@ -0,0 +1,134 @@
+//! Table driven CRC implementations shared by the protocol and the firmware.
+//!
+//! The lookup tables are computed at compile time. Both checksums can be computed in one go with
+//! [`crc16()`] and [`crc32()`], or incrementally with [`Crc16`] and [`Crc32`].
+
+/// The CRC-16/CCITT lookup table (polynomial `0x1021`, not reflected).
+static CRC16_TABLE: [u16; 256] = crc16_table();
+
+/// The CRC-32 lookup table (reflected polynomial `0xedb88320`).
+static CRC32_TABLE: [u32; 256] = crc32_table();
+
+const fn crc16_table() -> [u16; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = (i as u16) << 8;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+const fn crc32_table() -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+/// An incremental CRC-16/CCITT-FALSE computation.
+///
+/// Polynomial `0x1021`, initial value `0xffff`, no reflection and no final XOR. The checksum of
+/// `b"123456789"` is `0x29b1`.
+#[derive(Clone, Copy)]
+pub struct Crc16 {
+    crc: u16,
+}
+
+impl Crc16 {
+    /// Start a new computation.
+    pub const fn new() -> Self {
+        Self { crc: 0xffff }
+    }
+
+    /// Feed `data` to the computation.
+    pub fn update(&mut self, data: &[u8]) {
+        for &byte in data {
+            let index = ((self.crc >> 8) as u8 ^ byte) as usize;
+            self.crc = (self.crc << 8) ^ CRC16_TABLE[index];
+        }
+    }
+
+    /// The checksum of all the data fed so far.
+    pub const fn finish(&self) -> u16 {
+        self.crc
+    }
+}
+
+impl Default for Crc16 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An incremental CRC-32 (ISO-HDLC, as used by zlib and Ethernet) computation.
+///
+/// Reflected polynomial `0xedb88320`, initial value and final XOR `0xffffffff`. The checksum of
+/// `b"123456789"` is `0xcbf43926`.
+#[derive(Clone, Copy)]
+pub struct Crc32 {
+    crc: u32,
+}
+
+impl Crc32 {
+    /// Start a new computation.
+    pub const fn new() -> Self {
+        Self { crc: 0xffff_ffff }
+    }
+
+    /// Feed `data` to the computation.
+    pub fn update(&mut self, data: &[u8]) {
+        for &byte in data {
+            let index = (self.crc as u8 ^ byte) as usize;
+            self.crc = (self.crc >> 8) ^ CRC32_TABLE[index];
+        }
+    }
+
+    /// The checksum of all the data fed so far.
+    pub const fn finish(&self) -> u32 {
+        self.crc ^ 0xffff_ffff
+    }
+}
+
+impl Default for Crc32 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Compute the CRC-16/CCITT-FALSE checksum of `data`, see [`Crc16`].
+pub fn crc16(data: &[u8]) -> u16 {
+    let mut crc = Crc16::new();
+    crc.update(data);
+    crc.finish()
+}
+
+/// Compute the CRC-32 checksum of `data`, see [`Crc32`].
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = Crc32::new();
+    crc.update(data);
+    crc.finish()
+}
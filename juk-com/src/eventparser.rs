@@ -5,7 +5,8 @@ use vte::{Params, Parser, Perform};
 /// An event output from [`vte::Parser`].
 ///
 /// This enum represents decoded events from the parser.
-#[derive(defmt::Format, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Event {
     /// A printable character was recieved.
     Print(char),
@@ -16,10 +17,34 @@ pub enum Event {
     /// Note that not all ANSI escape sequences are decoded and returned. For the list of all
     /// sequences, which can be obtained from the [`EventParser`], see [`Key`].
     KeyEvent(Key),
+    /// A report sent by the terminal in answer to a query.
+    Report(Report),
+    /// ALT + digit was pressed, the payload is the digit.
+    ///
+    /// The digits form a numeric argument (a repeat count) of the next key event.
+    Argument(u8),
+}
+
+/// A report decoded from an ANSI escape sequence sent by the terminal.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Report {
+    /// The primary device attributes (`<ESC>[?...c`).
+    DeviceAttributes {
+        /// The ANSI color attribute (`22`) was reported.
+        color: bool,
+    },
+    /// The state of a DEC private mode (`<ESC>[?<mode>;<state>$y`).
+    Mode {
+        mode: u16,
+        /// The mode is known to the terminal (the state is not `0`).
+        supported: bool,
+    },
 }
 
 /// A key event decoded from an ANSI escape sequence.
-#[derive(defmt::Format, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Key {
     ArrowUp,
     ArrowDown,
@@ -131,7 +156,25 @@ impl Perform for EventBuf {
     }
 
     fn csi_dispatch(&mut self, params: &Params, intermediates: &[u8], ignore: bool, action: char) {
-        if ignore || !intermediates.is_empty() {
+        if ignore {
+            return;
+        }
+
+        let report = match (intermediates, action) {
+            (b"?", 'c') => Some(Report::DeviceAttributes {
+                color: params.iter().any(|p| p.first() == Some(&22)),
+            }),
+            (b"?$", 'y') => Some(Report::Mode {
+                mode: get_param(params, 0),
+                supported: get_param(params, 1) != 0,
+            }),
+            _ => None,
+        };
+        if let Some(report) = report {
+            self.event = Some(Event::Report(report));
+            return;
+        }
+        if !intermediates.is_empty() {
             return;
         }
 
@@ -158,4 +201,11 @@ impl Perform for EventBuf {
 
         self.event = Some(Event::KeyEvent(key));
     }
+
+    fn esc_dispatch(&mut self, intermediates: &[u8], ignore: bool, byte: u8) {
+        // ALT + key is sent as <ESC> followed by the key
+        if !ignore && intermediates.is_empty() && byte.is_ascii_digit() {
+            self.event = Some(Event::Argument(byte - b'0'));
+        }
+    }
 }
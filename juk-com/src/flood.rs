@@ -0,0 +1,76 @@
+//! Detection of input floods in the text mode.
+
+use esp_hal::time::{Duration, Instant};
+
+/// Length of the window in which the suspicious bytes are counted.
+const WINDOW: Duration = Duration::from_millis(100);
+
+/// Number of suspicious bytes in a single [`WINDOW`] considered a flood.
+///
+/// Typing, pasting text or holding a key down never comes close to this, binary data dumped into
+/// the text mode does.
+const THRESHOLD: u32 = 64;
+
+/// What to do with a byte checked by [`FloodGuard::check()`].
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    /// Process the byte.
+    Accept,
+    /// The flood has just ended, reset the parser and process the byte.
+    Resume,
+    /// A flood has just been detected, drop the byte and notify the user.
+    Flood,
+    /// Drop the byte.
+    Drop,
+}
+
+/// A guard detecting a high rate of non-printable bytes in the text mode.
+///
+/// While a flood lasts, the input is dropped instead of being parsed and echoed.
+pub struct FloodGuard {
+    window_start: Instant,
+    count: u32,
+    flooded: bool,
+}
+
+impl FloodGuard {
+    /// Construct a new [`FloodGuard`].
+    pub fn new() -> Self {
+        Self {
+            window_start: Instant::now(),
+            count: 0,
+            flooded: false,
+        }
+    }
+
+    /// Account for `byte` and decide what to do with it.
+    pub fn check(&mut self, byte: u8) -> Verdict {
+        let mut resumed = false;
+        if self.window_start.elapsed() >= WINDOW {
+            if self.flooded && self.count < THRESHOLD {
+                debug!("Text mode input flood ended");
+                self.flooded = false;
+                resumed = true;
+            }
+            self.window_start = Instant::now();
+            self.count = 0;
+        }
+
+        // C0 control codes, DEL and bytes outside of ASCII
+        if !(0x20..0x7f).contains(&byte) {
+            self.count += 1;
+        }
+
+        if self.flooded {
+            Verdict::Drop
+        } else if self.count >= THRESHOLD {
+            warn!("Text mode input flood detected, suppressing echo");
+            self.flooded = true;
+            Verdict::Flood
+        } else if resumed {
+            Verdict::Resume
+        } else {
+            Verdict::Accept
+        }
+    }
+}
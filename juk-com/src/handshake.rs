@@ -0,0 +1,94 @@
+//! The handshake opening a binary mode session of [`crate::Interface`].
+//!
+//! After switching to the binary mode, the host sends a [`Hello`] frame announcing its protocol
+//! version. The device answers with a [`Version`] frame describing what it supports, so that the
+//! host can adapt to older firmware and refuse the operations the device does not know about.
+//!
+//! Both messages are sent COBS encoded (see [`crate::cobs`]) to the
+//! [`CONTROL`](crate::router::CONTROL) endpoint, the multi-byte fields are little endian. The
+//! encodings below are the payloads, without the endpoint byte.
+
+/// The version of the binary protocol implemented by this crate.
+///
+/// Version 2 added the endpoint byte to every message, see [`crate::router`].
+pub const PROTOCOL_VERSION: u8 = 2;
+
+/// The message type of [`Hello`].
+const HELLO: u8 = 0x01;
+
+/// The message type of [`Version`].
+const VERSION: u8 = 0x02;
+
+/// The hello message sent by the host, `[0x01, protocol]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Hello {
+    /// The protocol version of the host.
+    pub protocol: u8,
+}
+
+impl Hello {
+    /// Length of the encoded message.
+    pub const LEN: usize = 2;
+
+    /// Encode the message.
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        [HELLO, self.protocol]
+    }
+
+    /// Decode the message from a decoded frame, `None` if `frame` is not a [`Hello`].
+    pub fn from_bytes(frame: &[u8]) -> Option<Self> {
+        match *frame {
+            [HELLO, protocol] => Some(Self { protocol }),
+            _ => None,
+        }
+    }
+}
+
+/// The answer of the device to [`Hello`], `[0x02, protocol, features (4 bytes), max_frame (2
+/// bytes)]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Version {
+    /// The protocol version of the device.
+    pub protocol: u8,
+    /// The optional features supported by the device, one bit per feature.
+    ///
+    /// No optional features are defined by the protocol versions 1 and 2, the unknown bits
+    /// should be ignored.
+    pub features: u32,
+    /// The longest decoded frame the device accepts.
+    pub max_frame: u16,
+}
+
+impl Version {
+    /// Length of the encoded message.
+    pub const LEN: usize = 8;
+
+    /// Encode the message.
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mut bytes = [0; Self::LEN];
+        bytes[0] = VERSION;
+        bytes[1] = self.protocol;
+        bytes[2..6].copy_from_slice(&self.features.to_le_bytes());
+        bytes[6..8].copy_from_slice(&self.max_frame.to_le_bytes());
+        bytes
+    }
+
+    /// Decode the message from a decoded frame, `None` if `frame` is not a [`Version`].
+    pub fn from_bytes(frame: &[u8]) -> Option<Self> {
+        let [VERSION, protocol, f0, f1, f2, f3, m0, m1] = *frame else {
+            return None;
+        };
+        Some(Self {
+            protocol,
+            features: u32::from_le_bytes([f0, f1, f2, f3]),
+            max_frame: u16::from_le_bytes([m0, m1]),
+        })
+    }
+
+    /// Check whether the feature `bit` is supported.
+    pub fn supports(&self, bit: u32) -> bool {
+        bit < 32 && self.features & (1 << bit) != 0
+    }
+}
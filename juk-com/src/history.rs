@@ -23,6 +23,15 @@ impl History {
         }
     }
 
+    /// Drop the oldest entries, keeping at most `keep` of the newest ones.
+    pub fn trim(&mut self, keep: usize) {
+        while self.entries.len() > keep {
+            self.entries.pop_front();
+        }
+        self.viewing_entry = None;
+        self.saved_line = None;
+    }
+
     /// Push `line` to the history.
     ///
     /// If `line` is empty or the same as the previous one, it is not pushed.
@@ -1,12 +1,20 @@
 //! The [`Interface`] struct implementation.
 
-use alloc::vec::Vec;
-use core::mem;
+use alloc::{string::String, vec::Vec};
+use core::{convert::Infallible, future::Future, mem, pin::pin};
+
+use embassy_futures::select::{Either, Either3, select3};
+use embassy_time::{Duration, Instant, Timer};
 
 use crate::{
     Input,
+    Picker,
+    Prompt,
     Terminal,
-    eventparser::{Event, EventParser, Key},
+    batch::Batch,
+    capabilities::{self, BRACKETED_PASTE_MODE, Capabilities},
+    eventparser::{Event, EventParser, Key, Report},
+    flood::{FloodGuard, Verdict},
     history::History,
     linebuffer::LineBuffer,
 };
@@ -17,6 +25,38 @@ const MOTD_TEXT: &'static str = "\r\n\x1b[1;32m*\x1b[0m Switching to text mode\r
 /// The message to display when switching to binary mode.
 const MOTD_BINARY: &'static str = "\r\n\x1b[1;32m*\x1b[0m Switching to binary mode\r\n\x1b[1;32m*\x1b[0m Press \x1b[1;37mCTRL + Space\x1b[0m once or twice to leave\r\n";
 
+/// Size of the input buffer filled with [`Terminal::read()`].
+const RX_BUFFER_SIZE: usize = 64;
+
+/// The longest line accepted in the text mode, in characters.
+const MAX_LINE_LEN: usize = 256;
+
+/// How [`Interface`] signals rejected input, see [`Interface::set_bell()`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum BellStyle {
+    /// Ring the bell with [`Terminal::bell()`].
+    #[default]
+    Audible,
+    /// Flash the terminal with [`Terminal::visual_bell()`], falls back to the audible bell if the
+    /// terminal does not understand ANSI escape sequences.
+    Visual,
+    /// Do not signal rejected input.
+    Off,
+}
+
+impl BellStyle {
+    /// Get the bell style from its name: `on`, `visual` or `off`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "on" => Some(Self::Audible),
+            "visual" => Some(Self::Visual),
+            "off" => Some(Self::Off),
+            _ => None,
+        }
+    }
+}
+
 /// The operating mode of [`Interface`].
 ///
 /// Used to track state of the [`Interface`] state machine.
@@ -26,6 +66,17 @@ enum InterfaceMode {
     Text,
 }
 
+/// State of the terminal capability probe.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Probe {
+    /// The probe has not been run.
+    Idle,
+    /// Waiting for the answer until the deadline.
+    Pending(Instant),
+    /// The probe has finished.
+    Done,
+}
+
 /// The main REPL + binary interface struct.
 ///
 /// This structure behaves like a state machine with two states:
@@ -45,13 +96,56 @@ enum InterfaceMode {
 /// The output of this state machine is a variant of the [`Input`] enum denoting what has been
 /// recieved on the interface allowing the user to take appropriate action.
 ///
+/// In the text state, nested interactions can push their own prompt context with
+/// [`Interface::push_prompt()`]. The line being edited in the previous context is kept aside and
+/// restored by [`Interface::pop_prompt()`]. A choice from a list can be made with a [`Picker`]
+/// instead, see [`Interface::start_picker()`].
+///
+/// The capabilities of the terminal can be found with [`Interface::probe()`]. If the terminal does
+/// not answer the probe, the interface stops relying on ANSI escape sequences.
+///
+/// Rejected input, e.g. a key moving the cursor past the end of the line or a line longer than
+/// the limit, rings the bell selected with [`Interface::set_bell()`].
+///
+/// Like in readline, the cursor movement and deletion keys accept a numeric argument typed with
+/// ALT + digits before the key, e.g. ALT + 5 followed by the left arrow moves the cursor five
+/// characters. With an argument, CTRL + D deletes characters instead of ending the transmission.
+///
 /// To use this struct's functionality, a type implementing the [`Terminal`] trait is required.
 pub struct Interface {
     mode: InterfaceMode,
+    text_notice: &'static str,
+    binary_notice: &'static str,
+    probe: Probe,
+    capabilities: Capabilities,
+    bell: BellStyle,
+    idle_timeout: Option<Duration>,
+    last_activity: Instant,
+    idle: bool,
+    /// Called whenever bytes are read from the terminal, see [`Interface::set_activity_hook()`].
+    activity_hook: Option<fn()>,
     parser: EventParser,
+    flood: FloodGuard,
     line: LineBuffer,
+    prompt: Prompt,
+    /// The suspended prompt contexts, the innermost one is last.
+    contexts: Vec<(Prompt, LineBuffer)>,
+    /// The active picker, it takes over the input until it finishes.
+    picker: Option<Picker>,
     history: History,
+    /// The numeric argument entered with ALT + digit, applied to the next key event.
+    argument: Option<usize>,
+    /// A string returned with [`Interface::recycle()`], used for the next submitted line.
+    spare: Option<String>,
     binary_buf: Vec<u8>,
+    /// The buffer of the output coalesced by [`Batch`], kept for reuse.
+    tx_buf: Vec<u8>,
+    /// The redraw of the rest of the line after an echoed character, deferred until the
+    /// characters following it in the input buffer are echoed, e.g. during a paste.
+    redraw_pending: bool,
+    rx_buf: [u8; RX_BUFFER_SIZE],
+    rx_pos: usize,
+    rx_len: usize,
 }
 
 impl Interface {
@@ -59,26 +153,291 @@ impl Interface {
     pub fn new() -> Self {
         Self {
             mode: InterfaceMode::Text,
+            text_notice: MOTD_TEXT,
+            binary_notice: MOTD_BINARY,
+            probe: Probe::Idle,
+            capabilities: Capabilities::default(),
+            bell: BellStyle::default(),
+            idle_timeout: None,
+            last_activity: Instant::now(),
+            idle: false,
+            activity_hook: None,
             parser: EventParser::new(),
+            flood: FloodGuard::new(),
             line: LineBuffer::new(),
+            prompt: Prompt::default(),
+            contexts: Vec::new(),
+            picker: None,
             history: History::new(),
+            argument: None,
+            spare: None,
             binary_buf: Vec::with_capacity(128),
+            tx_buf: Vec::new(),
+            redraw_pending: false,
+            rx_buf: [0; RX_BUFFER_SIZE],
+            rx_pos: 0,
+            rx_len: 0,
         }
     }
 
+    /// Replace the notices printed when switching to the text and to the binary mode, e.g. to
+    /// translate them.
+    pub fn set_notices(&mut self, text_mode: &'static str, binary_mode: &'static str) {
+        self.text_notice = text_mode;
+        self.binary_notice = binary_mode;
+    }
+
+    /// Select how rejected input is signalled.
+    pub fn set_bell(&mut self, bell: BellStyle) {
+        self.bell = bell;
+    }
+
+    /// How rejected input is signalled.
+    pub fn bell_style(&self) -> BellStyle {
+        self.bell
+    }
+
+    /// Signal rejected input with the selected [`BellStyle`].
+    pub async fn bell<T: Terminal>(&self, terminal: &mut T) -> Result<(), T::Error> {
+        match self.bell {
+            BellStyle::Audible => terminal.bell().await,
+            BellStyle::Visual if self.ansi(terminal) => terminal.visual_bell().await,
+            BellStyle::Visual => terminal.bell().await,
+            BellStyle::Off => Ok(()),
+        }
+    }
+
+    /// Fire [`Input::Idle`] when no input is recieved for `timeout`, `None` disables it.
+    ///
+    /// [`Input::Active`] is fired on the first input after [`Input::Idle`].
+    pub fn set_idle_timeout(&mut self, timeout: Option<Duration>) {
+        self.idle_timeout = timeout;
+        self.last_activity = Instant::now();
+    }
+
+    /// Call `hook` whenever bytes are read from the terminal, before they are processed, e.g. to
+    /// keep the chip awake while the user is typing. Unlike [`Input::Active`], the hook is called
+    /// for every read, including the bytes of an incomplete line or binary frame.
+    pub fn set_activity_hook(&mut self, hook: fn()) {
+        self.activity_hook = Some(hook);
+    }
+
+    /// The prompt of the active context.
+    pub fn prompt(&self) -> &Prompt {
+        &self.prompt
+    }
+
+    /// Enter a new prompt context.
+    ///
+    /// The line being edited is suspended until the context is left with
+    /// [`Interface::pop_prompt()`]. The caller should print the new prompt.
+    pub fn push_prompt(&mut self, prompt: Prompt) {
+        self.history.reset_view();
+        let line = mem::replace(&mut self.line, LineBuffer::new());
+        let prompt = mem::replace(&mut self.prompt, prompt);
+        self.contexts.push((prompt, line));
+    }
+
+    /// Leave the active prompt context and return its [`Prompt`].
+    ///
+    /// The suspended line of the previous context is restored, the caller should print its prompt
+    /// followed by [`Interface::redraw_line()`]. Returns `None` if the active context is the main
+    /// one, which cannot be left.
+    pub fn pop_prompt(&mut self) -> Option<Prompt> {
+        let (prompt, line) = self.contexts.pop()?;
+        self.line = line;
+        Some(mem::replace(&mut self.prompt, prompt))
+    }
+
+    /// Start picking an item from the list of `picker`, the list is printed right away.
+    ///
+    /// The picker takes over the text input until an item is picked with ENTER or the picker is
+    /// dismissed, either way [`Input::Picked`] is fired. CTRL + X dismisses the picker, while
+    /// CTRL + C closes it and fires [`Input::EndOfText`] as usual. The line being edited is left
+    /// untouched, the caller should print the prompt and redraw the line once the picker is done.
+    ///
+    /// Note that ESC does not dismiss the picker, as it starts the escape sequences of the keys.
+    pub async fn start_picker<T: Terminal>(
+        &mut self,
+        picker: Picker,
+        terminal: &mut T,
+    ) -> Result<(), T::Error> {
+        picker.draw(terminal, self.ansi(terminal)).await?;
+        self.picker = Some(picker);
+        Ok(())
+    }
+
+    /// Close the active picker without firing [`Input::Picked`], e.g. when the command waiting
+    /// for it has been cancelled. Returns `false` if no picker is active.
+    pub fn close_picker(&mut self) -> bool {
+        self.picker.take().is_some()
+    }
+
+    /// Free the memory held by the interface which is not essential, e.g. when the heap runs low.
+    ///
+    /// At most `keep_history` newest history entries are kept, the spare buffers are released.
+    pub fn release_memory(&mut self, keep_history: usize) {
+        self.history.trim(keep_history);
+        self.spare = None;
+        self.binary_buf.shrink_to_fit();
+        self.tx_buf = Vec::new();
+    }
+
+    /// Give back the string of a processed [`Input::Text`], so that its allocation is reused for
+    /// the next submitted line.
+    ///
+    /// Only one string is kept, recycling is optional.
+    pub fn recycle(&mut self, text: String) {
+        self.spare = Some(text);
+    }
+
+    /// Start the terminal capability probe.
+    ///
+    /// The queries are written to `terminal`, the answer is processed along with the input.
+    /// [`Input::Capabilities`] is fired when the answer is complete, or when the terminal does not
+    /// answer in time. Meant to be run once per session, e.g. when the user first interacts with
+    /// the interface.
+    pub async fn probe<T: Terminal>(&mut self, terminal: &mut T) -> Result<(), T::Error> {
+        debug!("Probing the terminal capabilities");
+        self.capabilities = Capabilities::default();
+        self.probe = Probe::Pending(Instant::now() + capabilities::TIMEOUT);
+        terminal.write(capabilities::QUERY).await
+    }
+
+    /// The capabilities of the terminal, `None` until [`Interface::probe()`] finishes.
+    pub fn capabilities(&self) -> Option<Capabilities> {
+        match self.probe {
+            Probe::Done => Some(self.capabilities),
+            _ => None,
+        }
+    }
+
+    /// Check whether ANSI escape sequences can be used with `terminal`.
+    fn ansi<T: Terminal>(&self, terminal: &T) -> bool {
+        terminal.ansi() && (self.probe != Probe::Done || self.capabilities.ansi)
+    }
+
+    /// Finish the capability probe with the capabilities found so far.
+    fn finish_probe(&mut self) -> Input {
+        self.probe = Probe::Done;
+        info!("Terminal capabilities: {:?}", self.capabilities);
+        Input::Capabilities(self.capabilities)
+    }
+
     /// Wait for an input event.
     ///
     /// The parser does not do any work, when this function is not running. The function will return
     /// an error, if the [`Terminal`] instance runs into an error. All other actions performed by
     /// this function and this parser are infallible.
+    ///
+    /// Input is read in bulk using [`Terminal::read()`]. Bytes remaining after an input event is
+    /// fired are kept and processed by the next call. The echo of the bytes read at once is
+    /// written to the terminal in a single write, so that a paste is echoed quickly even at a low
+    /// baud rate.
     pub async fn get_input<T: Terminal>(&mut self, terminal: &mut T) -> Result<Input, T::Error> {
+        match self
+            .get_input_or(terminal, core::future::pending::<Infallible>())
+            .await?
+        {
+            Either::First(input) => Ok(input),
+            Either::Second(never) => match never {},
+        }
+    }
+
+    /// Wait for an input event or for `other` to complete, whichever comes first.
+    ///
+    /// Behaves like [`Self::get_input()`], but `other` is polled while the interface waits for more
+    /// input. Buffered bytes are processed first and `other` never interrupts an echo or a redraw,
+    /// so the terminal is left in a consistent state when `other` completes. This makes it
+    /// possible to write to the terminal in between the input events, e.g. output of a command
+    /// running in another task.
+    ///
+    /// The pending [`Terminal::read()`] is dropped when `other` completes, so it has to be
+    /// cancellation safe.
+    pub async fn get_input_or<T: Terminal, F: Future>(
+        &mut self,
+        terminal: &mut T,
+        other: F,
+    ) -> Result<Either<Input, F::Output>, T::Error> {
+        let mut batch = Batch::new(terminal, mem::take(&mut self.tx_buf));
+        let result = self.get_input_batched(&mut batch, other).await;
+        let committed = batch.commit().await;
+        self.tx_buf = batch.release();
+        let input = result?;
+        committed?;
+        Ok(input)
+    }
+
+    /// Helper for [`Self::get_input_or()`] writing the output to `terminal` in batches.
+    async fn get_input_batched<T: Terminal, F: Future>(
+        &mut self,
+        terminal: &mut Batch<'_, T>,
+        other: F,
+    ) -> Result<Either<Input, F::Output>, T::Error> {
+        let mut other = pin!(other);
         loop {
-            let byte = terminal.read_byte().await?;
+            if self.rx_pos == self.rx_len {
+                // the output is complete before waiting, a write cannot be cancelled by `other`
+                self.finish_redraw(terminal).await?;
+                terminal.commit().await?;
+                let probe_deadline = match self.probe {
+                    Probe::Pending(deadline) => Some(deadline),
+                    _ => None,
+                };
+                let idle_deadline = match self.idle_timeout {
+                    Some(timeout) if !self.idle => Some(self.last_activity + timeout),
+                    _ => None,
+                };
+                let deadline = probe_deadline.into_iter().chain(idle_deadline).min();
+                let timeout = async move {
+                    match deadline {
+                        Some(deadline) => Timer::at(deadline).await,
+                        None => core::future::pending().await,
+                    }
+                };
+
+                match select3(terminal.read(&mut self.rx_buf), other.as_mut(), timeout).await {
+                    Either3::First(read) => self.rx_len = read?,
+                    Either3::Second(output) => return Ok(Either::Second(output)),
+                    Either3::Third(())
+                        if probe_deadline.is_some_and(|deadline| deadline <= Instant::now()) =>
+                    {
+                        warn!("The terminal did not answer the capability probe");
+                        return Ok(Either::First(self.finish_probe()));
+                    }
+                    Either3::Third(()) => {
+                        debug!("The interface is idle");
+                        self.idle = true;
+                        return Ok(Either::First(Input::Idle));
+                    }
+                }
+                self.rx_pos = 0;
+                self.last_activity = Instant::now();
+                if let Some(hook) = self.activity_hook {
+                    hook();
+                }
+                if mem::take(&mut self.idle) {
+                    return Ok(Either::First(Input::Active));
+                }
+                continue;
+            }
+
+            let byte = self.rx_buf[self.rx_pos];
+            self.rx_pos += 1;
+            if self.mode == InterfaceMode::Text {
+                match self.flood.check(byte) {
+                    Verdict::Accept => (),
+                    // the flood could have left the parser in the middle of a sequence
+                    Verdict::Resume => self.parser = EventParser::new(),
+                    Verdict::Flood => return Ok(Either::First(Input::Overrun)),
+                    Verdict::Drop => continue,
+                }
+            }
             if let Some(input) = match self.mode {
                 InterfaceMode::Binary => self.binary_dispatch(byte, terminal).await?,
                 InterfaceMode::Text => self.text_dispatch(byte, terminal).await?,
             } {
-                return Ok(input);
+                return Ok(Either::First(input));
             }
         }
     }
@@ -92,8 +451,8 @@ impl Interface {
     ) -> Result<Option<Input>, T::Error> {
         if byte == 0x00 {
             if self.binary_buf.is_empty() {
-                defmt::debug!("Binary mode got an empty frame, switching input mode to text");
-                terminal.write(MOTD_TEXT.as_bytes()).await?;
+                debug!("Binary mode got an empty frame, switching input mode to text");
+                terminal.write(self.text_notice.as_bytes()).await?;
                 self.mode = InterfaceMode::Text;
                 Ok(Some(Input::EndOfText))
             } else {
@@ -115,12 +474,12 @@ impl Interface {
         terminal: &mut T,
     ) -> Result<Option<Input>, T::Error> {
         if let Some(event) = self.parser.advance(byte) {
-            defmt::trace!("Text mode event: {:?}", event);
+            trace!("Text mode event: {:?}", event);
             let input = self.run_event(event, terminal).await?;
 
             if self.parser.terminated() {
-                defmt::debug!("Text mode parser terminated, switching input mode to binary");
-                terminal.write(MOTD_BINARY.as_bytes()).await?;
+                debug!("Text mode parser terminated, switching input mode to binary");
+                terminal.write(self.binary_notice.as_bytes()).await?;
                 self.parser.unterminate();
                 self.mode = InterfaceMode::Binary;
             }
@@ -138,14 +497,43 @@ impl Interface {
         event: Event,
         terminal: &mut T,
     ) -> Result<Option<Input>, T::Error> {
+        if !matches!(event, Event::Print(_)) {
+            self.finish_redraw(terminal).await?;
+        }
+        if self.picker.is_some() {
+            return self.run_picker_event(event, terminal).await;
+        }
+
+        let argument = match event {
+            Event::Argument(digit) => {
+                // no repetition can do more than the longest line
+                let argument = self.argument.unwrap_or(0) * 10 + digit as usize;
+                self.argument = Some(argument.min(MAX_LINE_LEN));
+                return Ok(None);
+            }
+            // the reports are not typed by the user
+            Event::Report(_) => None,
+            _ => self.argument.take(),
+        };
+
         match event {
             Event::Print(c) => {
+                if self.line.len() >= MAX_LINE_LEN {
+                    self.bell(terminal).await?;
+                    return Ok(None);
+                }
                 self.history.reset_view();
                 self.line.insert_char(c);
                 let mut b = [0; 4];
-                let s = c.encode_utf8(&mut b);
+                let s = if self.prompt.is_masked() {
+                    "*"
+                } else {
+                    &*c.encode_utf8(&mut b)
+                };
                 terminal.write(s.as_bytes()).await?;
-                self.redraw_from_cursor(terminal).await?;
+                if self.ansi(terminal) && self.line.cursor_pos() < self.line.as_str().len() {
+                    self.redraw_pending = true;
+                }
                 Ok(None)
             }
             Event::Execute(b) => match b {
@@ -164,7 +552,10 @@ impl Interface {
                 }
                 // CTRL + D (EOT)
                 0x04 => {
-                    if self.line.is_empty() {
+                    if let Some(count) = argument {
+                        self.run_key_repeated(Key::Delete, count, terminal).await?;
+                        Ok(None)
+                    } else if self.line.is_empty() {
                         terminal.write(b"^D\r\n").await?;
                         Ok(Some(Input::EndOfTransmission))
                     } else {
@@ -179,9 +570,14 @@ impl Interface {
                 // CTRL + M (CR) [ENTER]
                 0x0d => {
                     terminal.write(b"\r\n").await?;
-                    let text = self.line.take();
+                    let text = match self.spare.take() {
+                        Some(spare) => self.line.take_with(spare),
+                        None => self.line.take(),
+                    };
                     self.line.clear();
-                    self.history.add(&text);
+                    if self.prompt.has_history() {
+                        self.history.add(&text);
+                    }
                     self.history.reset_view();
                     Ok(Some(Input::Text(text)))
                 }
@@ -193,19 +589,126 @@ impl Interface {
                 _ => Ok(None),
             },
             Event::KeyEvent(key) => {
-                self.run_key_event(key, terminal).await?;
+                self.run_key_repeated(key, argument.unwrap_or(1), terminal)
+                    .await?;
                 Ok(None)
             }
+            Event::Report(report) => Ok(self.run_report(report)),
+            Event::Argument(_) => Ok(None),
+        }
+    }
+
+    /// Perform the action of `key` `count` times.
+    ///
+    /// The repetition stops at the first rejected action, ringing the bell.
+    async fn run_key_repeated<T: Terminal>(
+        &mut self,
+        key: Key,
+        count: usize,
+        terminal: &mut T,
+    ) -> Result<(), T::Error> {
+        for _ in 0..count {
+            if !self.run_key_event(key, terminal).await? {
+                return self.bell(terminal).await;
+            }
+        }
+        Ok(())
+    }
+
+    /// Counterpart of [`Self::run_event()`] while a [`Picker`] is active.
+    async fn run_picker_event<T: Terminal>(
+        &mut self,
+        event: Event,
+        terminal: &mut T,
+    ) -> Result<Option<Input>, T::Error> {
+        let Some(picker) = self.picker.as_mut() else {
+            return Ok(None);
+        };
+
+        let selected = picker.selected();
+        let index = match event {
+            Event::KeyEvent(Key::ArrowUp) => selected.checked_sub(1),
+            Event::KeyEvent(Key::ArrowDown) => Some(selected + 1),
+            Event::KeyEvent(Key::Home) => Some(0),
+            Event::KeyEvent(Key::End) => picker.items().len().checked_sub(1),
+            // CTRL + M (CR) [ENTER]
+            Event::Execute(0x0d) => {
+                let item = self.picker.take().and_then(Picker::pick);
+                return Ok(Some(Input::Picked(item)));
+            }
+            // CTRL + C (ETX)
+            Event::Execute(0x03) => {
+                terminal.write(b"^C\r\n").await?;
+                self.picker = None;
+                return Ok(Some(Input::EndOfText));
+            }
+            // CTRL + X (CAN)
+            Event::Execute(0x18) => {
+                terminal.write(b"^X\r\n").await?;
+                self.picker = None;
+                return Ok(Some(Input::Picked(None)));
+            }
+            Event::Report(report) => return Ok(self.run_report(report)),
+            Event::Print(_) | Event::KeyEvent(_) => None,
+            Event::Execute(_) | Event::Argument(_) => return Ok(None),
+        };
+
+        match index {
+            Some(index) if picker.select(index) => {
+                if index != selected {
+                    let ansi = self.ansi(terminal);
+                    if let Some(picker) = &self.picker {
+                        picker.redraw(terminal, ansi).await?;
+                    }
+                }
+                Ok(None)
+            }
+            _ => {
+                self.bell(terminal).await?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Process a report of the terminal, the reports are only expected during the probe.
+    fn run_report(&mut self, report: Report) -> Option<Input> {
+        if !matches!(self.probe, Probe::Pending(_)) {
+            debug!("Unexpected terminal report: {:?}", report);
+            return None;
+        }
+
+        match report {
+            Report::DeviceAttributes { color } => {
+                self.capabilities.ansi = true;
+                self.capabilities.color = color;
+                Some(self.finish_probe())
+            }
+            Report::Mode { mode, supported } => {
+                if mode == BRACKETED_PASTE_MODE {
+                    self.capabilities.bracketed_paste = supported;
+                }
+                None
+            }
         }
     }
 
     /// Helper for [`Self::run_event()`] to avoid excessive indentation.
+    ///
+    /// Returns `false` if the action was rejected, e.g. the cursor is already at the end of the
+    /// line.
     #[inline]
     async fn run_key_event<T: Terminal>(
         &mut self,
         key: Key,
         terminal: &mut T,
-    ) -> Result<(), T::Error> {
+    ) -> Result<bool, T::Error> {
+        if !self.prompt.has_history() && matches!(key, Key::ArrowUp | Key::ArrowDown) {
+            return Ok(true);
+        }
+        if !self.ansi(terminal) {
+            return self.run_plain_key_event(key, terminal).await;
+        }
+
         match key {
             Key::ArrowUp => {
                 if let Some(text) = self.history.previous(self.line.as_str()) {
@@ -232,11 +735,15 @@ impl Interface {
             Key::ArrowRight => {
                 if self.line.move_cursor_right() {
                     terminal.cursor_right().await?;
+                } else {
+                    return Ok(false);
                 }
             }
             Key::ArrowLeft => {
                 if self.line.move_cursor_left() {
                     terminal.cursor_left().await?;
+                } else {
+                    return Ok(false);
                 }
             }
             Key::Home => {
@@ -255,11 +762,15 @@ impl Interface {
                 if self.line.delete_before_cursor() {
                     terminal.cursor_left().await?;
                     self.redraw_from_cursor(terminal).await?;
+                } else {
+                    return Ok(false);
                 }
             }
             Key::Delete => {
                 if self.line.delete_at_cursor() {
                     self.redraw_from_cursor(terminal).await?;
+                } else {
+                    return Ok(false);
                 }
             }
             Key::CtrlBackspace => {
@@ -286,6 +797,56 @@ impl Interface {
                 }
             }
         }
+        Ok(true)
+    }
+
+    /// Counterpart of [`Self::run_key_event()`] for terminals without ANSI escape sequences.
+    ///
+    /// The cursor is kept at the end of the line, so the keys moving it are ignored and the
+    /// characters are erased using backspaces.
+    async fn run_plain_key_event<T: Terminal>(
+        &mut self,
+        key: Key,
+        terminal: &mut T,
+    ) -> Result<bool, T::Error> {
+        match key {
+            Key::ArrowUp | Key::ArrowDown => {
+                let count = self.line.cursor_char_pos();
+                let text = match key {
+                    Key::ArrowUp => self.history.previous(self.line.as_str()),
+                    _ => self.history.next(),
+                };
+                if let Some(text) = text {
+                    for _ in 0..count {
+                        terminal.write(b"\x08 \x08").await?;
+                    }
+                    self.line.load(text);
+                    terminal.write(text.as_bytes()).await?;
+                }
+            }
+            Key::Backspace => {
+                if self.line.delete_before_cursor() {
+                    terminal.write(b"\x08 \x08").await?;
+                } else {
+                    return Ok(false);
+                }
+            }
+            Key::CtrlBackspace => {
+                let count = self.line.delete_word_left();
+                for _ in 0..count {
+                    terminal.write(b"\x08 \x08").await?;
+                }
+            }
+            _ => (),
+        }
+        Ok(true)
+    }
+
+    /// Perform the redraw deferred by [`Event::Print`], if any.
+    async fn finish_redraw<T: Terminal>(&mut self, terminal: &mut T) -> Result<(), T::Error> {
+        if mem::take(&mut self.redraw_pending) {
+            self.redraw_from_cursor(terminal).await?;
+        }
         Ok(())
     }
 
@@ -296,7 +857,7 @@ impl Interface {
         let cursor_pos = self.line.cursor_pos();
         terminal.save_cursor_pos().await?;
         let remaining = &self.line.as_str()[cursor_pos..];
-        terminal.write(remaining.as_bytes()).await?;
+        self.write_text(remaining, terminal).await?;
         terminal.restore_cursor_pos().await?;
         Ok(())
     }
@@ -305,8 +866,13 @@ impl Interface {
     ///
     /// Assumes that the cursor is at an empty prompt.
     pub async fn redraw_line<T: Terminal>(&self, terminal: &mut T) -> Result<(), T::Error> {
+        if !self.ansi(terminal) {
+            // the cursor is always at the end of the line in this case
+            return self.write_text(self.line.as_str(), terminal).await;
+        }
+
         terminal.save_cursor_pos().await?;
-        terminal.write(self.line.as_str().as_bytes()).await?;
+        self.write_text(self.line.as_str(), terminal).await?;
         terminal.restore_cursor_pos().await?;
         // synchronise the cursor position with the buffer
         let count = self.line.cursor_char_pos();
@@ -315,4 +881,16 @@ impl Interface {
         }
         Ok(())
     }
+
+    /// Write a part of the line content, masked if the active prompt requires it.
+    async fn write_text<T: Terminal>(&self, text: &str, terminal: &mut T) -> Result<(), T::Error> {
+        if self.prompt.is_masked() {
+            for _ in text.chars() {
+                terminal.write(b"*").await?;
+            }
+            Ok(())
+        } else {
+            terminal.write(text.as_bytes()).await
+        }
+    }
 }
@@ -2,17 +2,37 @@
 //!
 //! Implements the [`Interface`] struct meant to handle control input and the [`Terminal`] trait
 //! used to perform actions required by [`Interface`].
+//!
+//! # Logging
+//!
+//! The crate logs through `defmt` by default. Disable the default features and enable the `log`
+//! feature to log through the `log` crate instead.
 
 #![no_std]
 
 extern crate alloc;
 
+// must come first, so that the logging macros are visible in the other modules
+mod fmt;
+
+mod batch;
+mod capabilities;
 mod eventparser;
+mod flood;
 mod interface;
+mod picker;
+mod plain;
+mod prompt;
+mod style;
 mod terminal;
 
+pub mod cobs;
+pub mod crc;
+pub mod handshake;
 pub mod history;
 pub mod linebuffer;
+pub mod router;
+pub mod transport;
 
 use alloc::{string::String, vec::Vec};
 
@@ -34,7 +54,29 @@ pub enum Input {
     EndOfText,
     /// CTRL + D was pressed.
     EndOfTransmission,
+    /// A flood of non-printable bytes was detected in the text mode.
+    ///
+    /// The input is dropped without being echoed until the flood ends. This event is fired once
+    /// per flood.
+    Overrun,
+    /// The terminal capability probe has finished, see [`Interface::probe()`].
+    Capabilities(Capabilities),
+    /// No input has been recieved for the idle timeout, see [`Interface::set_idle_timeout()`].
+    Idle,
+    /// Input has been recieved after [`Input::Idle`].
+    ///
+    /// The input itself is processed by the next call.
+    Active,
+    /// The [`Picker`] started with [`Interface::start_picker()`] has finished.
+    ///
+    /// The payload is the picked item, `None` if the picker was dismissed with CTRL + X.
+    Picked(Option<String>),
 }
 
-pub use interface::Interface;
+pub use capabilities::Capabilities;
+pub use interface::{BellStyle, Interface};
+pub use picker::Picker;
+pub use plain::PlainTerminal;
+pub use prompt::Prompt;
+pub use style::{Color, Style};
 pub use terminal::Terminal;
@@ -67,6 +67,15 @@ impl LineBuffer {
     /// Since the resulting string is stripped, the position returned by 
     /// [`LineBuffer::cursor_pos()`] or [`LineBuffer::cursor_char_pos()`] is not valid for it.
     pub fn take(&mut self) -> String {
+        self.take_with(String::with_capacity(128))
+    }
+
+    /// Take the contents of the line buffer like [`LineBuffer::take()`], using `spare` as the new
+    /// buffer instead of allocating one.
+    ///
+    /// `spare` is cleared, its allocation is reused. This allows to recycle the strings returned
+    /// by the previous calls.
+    pub fn take_with(&mut self, mut spare: String) -> String {
         // strip in place, adapted from: https://docs.rs/string_more/latest/src/string_more/lib.rs.html#524
         let trimmed = self.buf.trim();
         let len = trimmed.len();
@@ -81,7 +90,8 @@ impl LineBuffer {
 
         self.buf.truncate(len);
         // take the old string
-        mem::replace(&mut self.buf, String::with_capacity(128))
+        spare.clear();
+        mem::replace(&mut self.buf, spare)
     }
 
     /// Insert a character at the cursor's position.
@@ -0,0 +1,130 @@
+//! The [`Picker`] list-selection widget of [`crate::Interface`].
+
+use alloc::{string::String, vec::Vec};
+
+use crate::{Style, Terminal};
+
+/// Style of the selected item.
+const SELECTED: Style = Style::new().with_bold().with_reverse();
+
+/// A list of items to pick one from, see [`crate::Interface::start_picker()`].
+///
+/// The selected item is highlighted and moved with the up and down arrows (or home and end),
+/// ENTER picks it. On terminals without ANSI escape sequences the list is printed once and the
+/// newly selected item is printed on every move.
+#[derive(Clone)]
+pub struct Picker {
+    title: String,
+    items: Vec<String>,
+    selected: usize,
+}
+
+impl Picker {
+    /// Construct a new [`Picker`] of `items`, with the first item selected.
+    pub fn new(title: impl Into<String>, items: Vec<String>) -> Self {
+        Self {
+            title: title.into(),
+            items,
+            selected: 0,
+        }
+    }
+
+    /// Select the item at `index` initially, e.g. the current setting. An out of range `index`
+    /// is ignored.
+    pub fn with_selected(mut self, index: usize) -> Self {
+        if index < self.items.len() {
+            self.selected = index;
+        }
+        self
+    }
+
+    /// The title printed above the items.
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// The items to pick from.
+    pub fn items(&self) -> &[String] {
+        &self.items
+    }
+
+    /// The index of the selected item.
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Consume the picker returning the selected item, `None` if there are no items.
+    pub(crate) fn pick(mut self) -> Option<String> {
+        if self.selected < self.items.len() {
+            Some(self.items.swap_remove(self.selected))
+        } else {
+            None
+        }
+    }
+
+    /// Select the item at `index`, returns `false` if it is out of range.
+    pub(crate) fn select(&mut self, index: usize) -> bool {
+        if index >= self.items.len() {
+            return false;
+        }
+        self.selected = index;
+        true
+    }
+
+    /// Print the title and the items, the cursor is left on the line below the list.
+    pub(crate) async fn draw<T: Terminal>(
+        &self,
+        terminal: &mut T,
+        ansi: bool,
+    ) -> Result<(), T::Error> {
+        terminal.write(self.title.as_bytes()).await?;
+        terminal.write(b"\r\n").await?;
+        for index in 0..self.items.len() {
+            self.draw_item(index, terminal, ansi).await?;
+        }
+        Ok(())
+    }
+
+    /// Update the list after the selection has changed.
+    pub(crate) async fn redraw<T: Terminal>(
+        &self,
+        terminal: &mut T,
+        ansi: bool,
+    ) -> Result<(), T::Error> {
+        if !ansi {
+            return self.draw_item(self.selected, terminal, false).await;
+        }
+
+        for _ in 0..self.items.len() {
+            terminal.cursor_up().await?;
+        }
+        for index in 0..self.items.len() {
+            terminal.clear_eol().await?;
+            self.draw_item(index, terminal, true).await?;
+        }
+        Ok(())
+    }
+
+    /// Print the item at `index` on its own line.
+    async fn draw_item<T: Terminal>(
+        &self,
+        index: usize,
+        terminal: &mut T,
+        ansi: bool,
+    ) -> Result<(), T::Error> {
+        let item = &self.items[index];
+        if index != self.selected {
+            terminal.write(b"  ").await?;
+            terminal.write(item.as_bytes()).await?;
+        } else if ansi {
+            terminal.set_style(SELECTED).await?;
+            terminal.write(b"> ").await?;
+            terminal.write(item.as_bytes()).await?;
+            terminal.reset_style().await?;
+        } else {
+            terminal.write(b"> ").await?;
+            terminal.write(item.as_bytes()).await?;
+        }
+        terminal.write(b"\r\n").await
+    }
+}
@@ -0,0 +1,130 @@
+//! [`PlainTerminal`] adapter for terminals without ANSI escape sequence support.
+
+use crate::Terminal;
+
+/// State of the escape sequence filter.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum FilterState {
+    /// Outside of an escape sequence.
+    Ground,
+    /// After an `<ESC>` byte.
+    Escape,
+    /// Inside of a control sequence (`<ESC>[`).
+    Csi,
+}
+
+/// A [`Terminal`] adapter, which can strip ANSI escape sequences from the output.
+///
+/// When the plain mode is enabled, all escape sequences written to the terminal are dropped and
+/// [`Terminal::ansi()`] returns `false`, so that [`crate::Interface`] does not rely on them. This
+/// is useful for minimal terminals and for capturing the output to a log.
+///
+/// Escape sequences split between several writes are handled correctly.
+pub struct PlainTerminal<T> {
+    inner: T,
+    plain: bool,
+    state: FilterState,
+}
+
+impl<T: Terminal> PlainTerminal<T> {
+    /// Wrap `inner`, the plain mode is disabled.
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            plain: false,
+            state: FilterState::Ground,
+        }
+    }
+
+    /// Check whether the plain mode is enabled.
+    pub fn plain(&self) -> bool {
+        self.plain
+    }
+
+    /// Enable or disable the plain mode.
+    pub fn set_plain(&mut self, plain: bool) {
+        self.plain = plain;
+        self.state = FilterState::Ground;
+    }
+
+    /// Get a mutable reference to the wrapped terminal.
+    pub fn inner_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Release the wrapped terminal.
+    pub fn release(self) -> T {
+        self.inner
+    }
+
+    /// Advance the escape sequence filter, returns `true` if `byte` should be written.
+    fn advance(&mut self, byte: u8) -> bool {
+        match self.state {
+            FilterState::Ground => {
+                if byte == 0x1b {
+                    self.state = FilterState::Escape;
+                    false
+                } else {
+                    true
+                }
+            }
+            FilterState::Escape => {
+                // two byte sequences (e.g. `<ESC>7`) end here
+                self.state = if byte == b'[' {
+                    FilterState::Csi
+                } else {
+                    FilterState::Ground
+                };
+                false
+            }
+            FilterState::Csi => {
+                // parameter (0x30-0x3f) and intermediate (0x20-0x2f) bytes continue the sequence
+                if !(0x20..=0x3f).contains(&byte) {
+                    self.state = FilterState::Ground;
+                }
+                false
+            }
+        }
+    }
+}
+
+impl<T: Terminal> Terminal for PlainTerminal<T> {
+    type Error = T::Error;
+
+    async fn read_byte(&mut self) -> Result<u8, Self::Error> {
+        self.inner.read_byte().await
+    }
+
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        self.inner.read(buf).await
+    }
+
+    async fn write(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        if !self.plain {
+            return self.inner.write(buf).await;
+        }
+
+        // write the runs of bytes outside of escape sequences
+        let mut start = 0;
+        for (i, &byte) in buf.iter().enumerate() {
+            if !self.advance(byte) {
+                if start < i {
+                    self.inner.write(&buf[start..i]).await?;
+                }
+                start = i + 1;
+            }
+        }
+        if start < buf.len() {
+            self.inner.write(&buf[start..]).await?;
+        }
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush().await
+    }
+
+    fn ansi(&self) -> bool {
+        !self.plain && self.inner.ansi()
+    }
+}
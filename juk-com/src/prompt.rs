@@ -0,0 +1,67 @@
+//! The [`Prompt`] struct used to configure the prompt contexts of [`crate::Interface`].
+
+use alloc::string::String;
+
+/// The prompt of the main context of [`crate::Interface`].
+const MAIN_PROMPT: &str = "$ ";
+
+/// Configuration of a prompt context.
+///
+/// The [`crate::Interface`] keeps a stack of prompt contexts, each with its own line buffer, so
+/// that nested interactions (e.g. a confirmation or a password entry) do not disturb the line
+/// being edited in the main context. See [`crate::Interface::push_prompt()`].
+#[derive(Clone)]
+pub struct Prompt {
+    text: String,
+    masked: bool,
+    history: bool,
+}
+
+impl Prompt {
+    /// Construct a new [`Prompt`] displaying `text`.
+    ///
+    /// By default the input is echoed and the history is disabled.
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            masked: false,
+            history: false,
+        }
+    }
+
+    /// Echo `*` instead of the typed characters, e.g. for a password entry.
+    pub fn masked(mut self) -> Self {
+        self.masked = true;
+        self
+    }
+
+    /// Record the entered lines in the history and allow browsing it.
+    pub fn with_history(mut self) -> Self {
+        self.history = true;
+        self
+    }
+
+    /// The prompt text.
+    ///
+    /// Note that the [`crate::Interface`] does not print the prompt by itself.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Check whether the input is masked.
+    pub fn is_masked(&self) -> bool {
+        self.masked
+    }
+
+    /// Check whether the history is enabled.
+    pub fn has_history(&self) -> bool {
+        self.history
+    }
+}
+
+impl Default for Prompt {
+    /// The prompt of the main context: `$ ` with the history enabled.
+    fn default() -> Self {
+        Self::new(MAIN_PROMPT).with_history()
+    }
+}
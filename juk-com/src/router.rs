@@ -0,0 +1,124 @@
+//! Routing of the binary mode messages to the subsystems handling them.
+//!
+//! Every decoded frame starts with an endpoint byte selecting the subsystem the rest of the
+//! message (the payload) is meant for. The subsystems register a [`Handler`] for their endpoint
+//! with a [`Router`], which dispatches the messages and prefixes the replies with the same
+//! endpoint byte, so the host can tell the answers of different subsystems apart.
+//!
+//! # Usage
+//!
+//! ```
+//! use juk_com::router::{Router, TELEMETRY};
+//!
+//! fn echo(payload: &[u8]) -> Option<Vec<u8>> {
+//!     Some(payload.to_vec())
+//! }
+//!
+//! let mut router = Router::new();
+//! router.register(TELEMETRY, echo).unwrap();
+//! assert_eq!(
+//!     router.route(&[TELEMETRY, 0x2a]),
+//!     Ok(Some(vec![TELEMETRY, 0x2a]))
+//! );
+//! ```
+
+use alloc::vec::Vec;
+
+/// The endpoint of the protocol control messages, e.g. the handshake (see
+/// [`crate::handshake`]).
+pub const CONTROL: u8 = 0x00;
+
+/// The endpoint of the LED frame streaming.
+pub const LED: u8 = 0x01;
+
+/// The endpoint of the file transfer.
+pub const FILE: u8 = 0x02;
+
+/// The endpoint of the remote shell.
+pub const SHELL: u8 = 0x03;
+
+/// The endpoint of the telemetry.
+pub const TELEMETRY: u8 = 0x04;
+
+/// A handler of the messages sent to an endpoint.
+///
+/// Takes the payload without the endpoint byte and returns the reply payload if there is one.
+pub type Handler = fn(&[u8]) -> Option<Vec<u8>>;
+
+/// An error returned by [`Router`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// The message has no endpoint byte.
+    Empty,
+    /// No handler is registered for the endpoint.
+    UnknownEndpoint(u8),
+    /// A handler is already registered for the endpoint.
+    AlreadyRegistered(u8),
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "empty message"),
+            Self::UnknownEndpoint(endpoint) => write!(f, "unknown endpoint 0x{endpoint:02x}"),
+            Self::AlreadyRegistered(endpoint) => {
+                write!(f, "endpoint 0x{endpoint:02x} already registered")
+            }
+        }
+    }
+}
+
+/// Dispatches the binary mode messages to the [`Handler`]s registered for their endpoints.
+#[derive(Default)]
+pub struct Router {
+    handlers: Vec<(u8, Handler)>,
+}
+
+impl Router {
+    /// Construct a new [`Router`] without any handlers.
+    pub const fn new() -> Self {
+        Self {
+            handlers: Vec::new(),
+        }
+    }
+
+    /// Register `handler` for the messages sent to `endpoint`.
+    pub fn register(&mut self, endpoint: u8, handler: Handler) -> Result<(), Error> {
+        if self.handler(endpoint).is_some() {
+            return Err(Error::AlreadyRegistered(endpoint));
+        }
+        self.handlers.push((endpoint, handler));
+        Ok(())
+    }
+
+    /// Remove the handler of `endpoint`, if there is one.
+    pub fn unregister(&mut self, endpoint: u8) {
+        self.handlers.retain(|&(e, _)| e != endpoint);
+    }
+
+    /// Pass a decoded message to the handler of its endpoint.
+    ///
+    /// Returns the reply message, prefixed with the endpoint byte, if the handler replied.
+    pub fn route(&self, message: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+        let (&endpoint, payload) = message.split_first().ok_or(Error::Empty)?;
+        let handler = self
+            .handler(endpoint)
+            .ok_or(Error::UnknownEndpoint(endpoint))?;
+
+        Ok(handler(payload).map(|reply| {
+            let mut message = Vec::with_capacity(reply.len() + 1);
+            message.push(endpoint);
+            message.extend_from_slice(&reply);
+            message
+        }))
+    }
+
+    /// The handler registered for `endpoint`.
+    fn handler(&self, endpoint: u8) -> Option<Handler> {
+        self.handlers
+            .iter()
+            .find(|&&(e, _)| e == endpoint)
+            .map(|&(_, handler)| handler)
+    }
+}
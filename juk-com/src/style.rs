@@ -0,0 +1,76 @@
+//! The [`Style`] of the text written with [`crate::Terminal::set_style()`].
+
+use alloc::{format, string::String};
+
+/// One of the 8 basic terminal colors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+/// A text style, the default is the plain text of the terminal.
+///
+/// ```
+/// use juk_com::{Color, Style};
+///
+/// const HIGHLIGHT: Style = Style::new().with_bold().with_fg(Color::Green);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Style {
+    /// Whether the text is bold.
+    pub bold: bool,
+    /// Whether the foreground and background colors are swapped, e.g. to highlight a selection.
+    pub reverse: bool,
+    /// The text color, `None` keeps the default color of the terminal.
+    pub fg: Option<Color>,
+}
+
+impl Style {
+    /// Construct a new plain [`Style`].
+    pub const fn new() -> Self {
+        Self {
+            bold: false,
+            reverse: false,
+            fg: None,
+        }
+    }
+
+    /// Make the text bold.
+    pub const fn with_bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    /// Swap the foreground and background colors.
+    pub const fn with_reverse(mut self) -> Self {
+        self.reverse = true;
+        self
+    }
+
+    /// Set the text color.
+    pub const fn with_fg(mut self, color: Color) -> Self {
+        self.fg = Some(color);
+        self
+    }
+
+    /// The ANSI SGR escape sequence selecting the style, e.g. `<ESC>[0;1;32m`.
+    ///
+    /// The sequence starts with a reset, so that no attribute of the previous style is kept.
+    pub(crate) fn sgr(&self) -> String {
+        let bold = if self.bold { ";1" } else { "" };
+        let reverse = if self.reverse { ";7" } else { "" };
+        match self.fg {
+            Some(color) => format!("\x1b[0{bold}{reverse};{}m", 30 + color as u8),
+            None => format!("\x1b[0{bold}{reverse}m"),
+        }
+    }
+}
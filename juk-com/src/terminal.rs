@@ -1,7 +1,13 @@
 //! [`Terminal`] trait helper for use with [`crate::Interface`].
 
+use embassy_time::{Duration, Timer};
 use esp_hal::uart::{IoError, Uart};
 
+use crate::Style;
+
+/// How long the screen stays in reverse video during [`Terminal::visual_bell()`].
+const VISUAL_BELL_DURATION: Duration = Duration::from_millis(100);
+
 /// Terminal trait used to implement the REPL interface.
 ///
 /// This trait should be implemented on types, which perform user I/O.
@@ -14,6 +20,23 @@ pub trait Terminal {
     /// If the input buffer is empty the implementation should asynchronously wait for a byte to
     /// become available.
     async fn read_byte(&mut self) -> Result<u8, Self::Error>;
+    /// Read bytes from the input source into `buf`, returning the number of bytes read.
+    ///
+    /// If the input buffer is empty the implementation should asynchronously wait for at least
+    /// one byte to become available, and then return as many bytes as are available without
+    /// waiting any further.
+    ///
+    /// The default implementation reads a single byte using [`Terminal::read_byte()`]. An
+    /// implementation should override it, if the input source supports bulk reads.
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        match buf.first_mut() {
+            Some(b) => {
+                *b = self.read_byte().await?;
+                Ok(1)
+            }
+            None => Ok(0),
+        }
+    }
     /// Write the entire contents of `buf` to the output sink.
     ///
     /// In standard Rust terminology this function should be named `write_all()`
@@ -22,6 +45,13 @@ pub trait Terminal {
     ///
     /// The implementation should make sure that all pending data is transmitted.
     async fn flush(&mut self) -> Result<(), Self::Error>;
+    /// Check whether the terminal understands ANSI escape sequences.
+    ///
+    /// If it does not, [`crate::Interface`] does not move the cursor and erases characters using
+    /// backspaces instead. The default implementation returns `true`.
+    fn ansi(&self) -> bool {
+        true
+    }
 
     /// Move the terminal cursor left.
     ///
@@ -31,6 +61,14 @@ pub trait Terminal {
         self.write(b"\x1b[D").await
     }
 
+    /// Move the terminal cursor up one line.
+    ///
+    /// The default implementation uses an ANSI escape sequence `<ESC>[A`. An implementation could
+    /// call a platform API instead.
+    async fn cursor_up(&mut self) -> Result<(), Self::Error> {
+        self.write(b"\x1b[A").await
+    }
+
     /// Move the terminal cursor right.
     ///
     /// The default implementation uses an ANSI escape sequence `<ESC>[C`. An implementation could
@@ -64,6 +102,55 @@ pub trait Terminal {
     async fn restore_cursor_pos(&mut self) -> Result<(), Self::Error> {
         self.write(b"\x1b[u").await
     }
+
+    /// Ring the terminal bell.
+    ///
+    /// The default implementation writes the BEL character (`0x07`).
+    async fn bell(&mut self) -> Result<(), Self::Error> {
+        self.write(b"\x07").await
+    }
+
+    /// Flash the terminal instead of ringing the bell.
+    ///
+    /// The default implementation switches the screen to reverse video for a moment using the ANSI
+    /// escape sequences `<ESC>[?5h` and `<ESC>[?5l`. An implementation could call a platform API
+    /// instead.
+    async fn visual_bell(&mut self) -> Result<(), Self::Error> {
+        self.write(b"\x1b[?5h").await?;
+        self.flush().await?;
+        Timer::after(VISUAL_BELL_DURATION).await;
+        self.write(b"\x1b[?5l").await
+    }
+
+    /// Set the [`Style`] of the text written next.
+    ///
+    /// The default implementation uses an ANSI SGR escape sequence, e.g. `<ESC>[0;1;32m`. Nothing
+    /// is written if the terminal does not understand ANSI escape sequences, see
+    /// [`Terminal::ansi()`].
+    async fn set_style(&mut self, style: Style) -> Result<(), Self::Error> {
+        if !self.ansi() {
+            return Ok(());
+        }
+        self.write(style.sgr().as_bytes()).await
+    }
+
+    /// Reset the style of the text written next to the plain text of the terminal.
+    ///
+    /// The default implementation uses an ANSI escape sequence `<ESC>[0m`, it is skipped just like
+    /// in [`Terminal::set_style()`].
+    async fn reset_style(&mut self) -> Result<(), Self::Error> {
+        if !self.ansi() {
+            return Ok(());
+        }
+        self.write(b"\x1b[0m").await
+    }
+
+    /// Write `text` in `style`, the style is reset afterwards.
+    async fn write_styled(&mut self, style: Style, text: &str) -> Result<(), Self::Error> {
+        self.set_style(style).await?;
+        self.write(text.as_bytes()).await?;
+        self.reset_style().await
+    }
 }
 
 impl<'d> Terminal for Uart<'d, esp_hal::Async> {
@@ -75,6 +162,10 @@ impl<'d> Terminal for Uart<'d, esp_hal::Async> {
         Ok(buf[0])
     }
 
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        Ok(self.read_async(buf).await?)
+    }
+
     async fn write(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
         let mut n = 0;
         while n < buf.len() {
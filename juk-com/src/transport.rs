@@ -0,0 +1,141 @@
+//! The [`FrameTransport`] trait decoupling the binary protocol from the link carrying it.
+//!
+//! The protocol layers (e.g. [`crate::handshake`] and [`crate::router`]) deal with whole decoded
+//! messages, a transport takes care of the framing. Two transports are provided:
+//! - [`CobsTransport`]: COBS frames over any [`Terminal`], e.g. a raw UART
+//! - [`InterfaceTransport`]: the binary mode of an [`Interface`]
+
+use alloc::vec::Vec;
+
+use crate::{Input, Interface, Terminal, cobs};
+
+/// An error returned by a [`FrameTransport`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TransportError<E> {
+    /// The underlying link failed.
+    Io(E),
+    /// A received frame could not be decoded, it is dropped.
+    Cobs(cobs::Error),
+    /// The link stopped carrying frames, e.g. the [`Interface`] left the binary mode.
+    Closed,
+}
+
+/// A link carrying the messages of the binary protocol.
+#[allow(async_fn_in_trait)]
+pub trait FrameTransport {
+    type Error;
+
+    /// Send a single message, the transport frames it.
+    async fn send_frame(&mut self, message: &[u8]) -> Result<(), Self::Error>;
+    /// Receive a single message into `buf`, returning its length.
+    ///
+    /// The messages longer than `buf` are dropped with an error.
+    async fn recv_frame(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error>;
+}
+
+/// COBS encode `message` and append the sentinel.
+fn encode_frame(message: &[u8]) -> Vec<u8> {
+    let mut frame = cobs::encode(message);
+    frame.push(cobs::SENTINEL);
+    frame
+}
+
+/// A [`FrameTransport`] sending COBS frames directly over a [`Terminal`], e.g. a UART dedicated
+/// to the binary protocol.
+pub struct CobsTransport<T> {
+    terminal: T,
+}
+
+impl<T: Terminal> CobsTransport<T> {
+    /// Construct a new [`CobsTransport`] over `terminal`.
+    pub fn new(terminal: T) -> Self {
+        Self { terminal }
+    }
+
+    /// Release the wrapped terminal.
+    pub fn release(self) -> T {
+        self.terminal
+    }
+}
+
+impl<T: Terminal> FrameTransport for CobsTransport<T> {
+    type Error = TransportError<T::Error>;
+
+    async fn send_frame(&mut self, message: &[u8]) -> Result<(), Self::Error> {
+        self.terminal
+            .write(&encode_frame(message))
+            .await
+            .map_err(TransportError::Io)
+    }
+
+    async fn recv_frame(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        let mut decoder = cobs::Decoder::new(buf);
+        // the decoder drops the rest of an invalid frame, the error is returned at its end
+        let mut error = None;
+        loop {
+            let byte = self
+                .terminal
+                .read_byte()
+                .await
+                .map_err(TransportError::Io)?;
+            match decoder.feed(byte) {
+                Ok(Some(message)) => return Ok(message.len()),
+                Ok(None) => (),
+                Err(e) => error = Some(e),
+            }
+            if let (cobs::SENTINEL, Some(e)) = (byte, error) {
+                return Err(TransportError::Cobs(e));
+            }
+        }
+    }
+}
+
+/// A [`FrameTransport`] over the binary mode of an [`Interface`].
+///
+/// The frames are received with [`Interface::get_input()`], so the interface has to be in the
+/// binary mode. [`TransportError::Closed`] is returned once it switches back to the text mode.
+pub struct InterfaceTransport<'a, T> {
+    interface: &'a mut Interface,
+    terminal: &'a mut T,
+}
+
+impl<'a, T: Terminal> InterfaceTransport<'a, T> {
+    /// Construct a new [`InterfaceTransport`] over `interface` reading from `terminal`.
+    pub fn new(interface: &'a mut Interface, terminal: &'a mut T) -> Self {
+        Self {
+            interface,
+            terminal,
+        }
+    }
+}
+
+impl<'a, T: Terminal> FrameTransport for InterfaceTransport<'a, T> {
+    type Error = TransportError<T::Error>;
+
+    async fn send_frame(&mut self, message: &[u8]) -> Result<(), Self::Error> {
+        self.terminal
+            .write(&encode_frame(message))
+            .await
+            .map_err(TransportError::Io)
+    }
+
+    async fn recv_frame(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        loop {
+            let input = self
+                .interface
+                .get_input(self.terminal)
+                .await
+                .map_err(TransportError::Io)?;
+            match input {
+                // the frame includes the sentinel
+                Input::Binary(frame) => {
+                    return cobs::decode_into(&frame[..frame.len() - 1], buf)
+                        .map_err(TransportError::Cobs);
+                }
+                Input::Idle | Input::Active | Input::Capabilities(_) => (),
+                _ => return Err(TransportError::Closed),
+            }
+        }
+    }
+}
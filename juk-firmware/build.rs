@@ -5,12 +5,44 @@ fn main() {
         .build_pattern(BuildPattern::RealTime)
         .build()
         .unwrap();
+    export_features();
     linker_be_nice();
     println!("cargo:rustc-link-arg=-Tdefmt.x");
     // make sure linkall.x is the last linker script (otherwise might cause problems with flip-link)
     println!("cargo:rustc-link-arg=-Tlinkall.x");
 }
 
+/// Export the enabled cargo features as a comma separated list in `JUK_FEATURES`.
+///
+/// Cargo passes the features as `CARGO_FEATURE_<NAME>` with the name uppercased and `-` replaced
+/// by `_`, which cannot be reversed, so the names are taken from the `[features]` table of the
+/// manifest instead (e.g. `lang-pl`, not `lang_pl`).
+fn export_features() {
+    let manifest = std::env::var("CARGO_MANIFEST_DIR").unwrap() + "/Cargo.toml";
+    let manifest = std::fs::read_to_string(manifest).unwrap();
+
+    let mut features: Vec<&str> = declared_features(&manifest)
+        .filter(|feature| {
+            let key = format!("CARGO_FEATURE_{}", feature.to_uppercase().replace('-', "_"));
+            std::env::var_os(key).is_some()
+        })
+        .collect();
+    features.sort();
+    println!("cargo:rustc-env=JUK_FEATURES={}", features.join(","));
+}
+
+/// The names of the features declared in the `[features]` table of `manifest`.
+fn declared_features(manifest: &str) -> impl Iterator<Item = &str> {
+    manifest
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| *line != "[features]")
+        .skip(1)
+        .take_while(|line| !line.starts_with('['))
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('=').map(|(name, _)| name.trim()))
+}
+
 fn linker_be_nice() {
     let args: Vec<String> = std::env::args().collect();
     if args.len() > 1 {
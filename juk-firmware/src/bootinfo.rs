@@ -0,0 +1,118 @@
+//! Boot diagnostics collected at startup.
+
+use esp_hal::{
+    ram,
+    rtc_cntl::{SocResetReason, reset_reason, wakeup_cause},
+    system::{Cpu, SleepSource},
+};
+
+/// Value of [`SESSION_MARKER`] while the firmware is running.
+const SESSION_RUNNING: u32 = 0x4a55_4b32;
+
+/// Marker persisted across resets, used to detect sessions which did not end cleanly.
+///
+/// The marker is set when [`BootInfo::collect`] runs and cleared by [`mark_clean_shutdown`]. It
+/// is zeroed on power-on.
+#[ram(unstable(rtc_fast, persistent))]
+static mut SESSION_MARKER: u32 = 0;
+
+/// Number of sessions in a row which did not end cleanly, persisted like [`SESSION_MARKER`].
+///
+/// Cleared by [`mark_clean_shutdown`] and [`mark_stable`]. Ignored after a power-on reset, as the
+/// RTC memory content is undefined then.
+#[ram(unstable(rtc_fast, persistent))]
+static mut UNCLEAN_STREAK: u32 = 0;
+
+/// Number of sessions in a row which have to end uncleanly for the next boot to enter the safe
+/// mode.
+const SAFE_MODE_THRESHOLD: u32 = 2;
+
+/// Diagnostic information about the current boot.
+#[derive(Clone, Copy)]
+pub struct BootInfo {
+    /// The reason of the last reset of the main core.
+    pub reset_reason: Option<SocResetReason>,
+    /// The source which woke the chip up from deep sleep.
+    pub wakeup_cause: SleepSource,
+    /// Whether the previous session ended without calling [`mark_clean_shutdown`], e.g. because
+    /// of a panic or a watchdog reset.
+    pub unclean_shutdown: bool,
+    /// Number of sessions in a row which did not end cleanly, including the previous one.
+    pub unclean_streak: u32,
+    /// The firmware should boot in the safe mode, because the previous sessions kept crashing.
+    ///
+    /// In the safe mode only the console is brought up, the optional subsystems (e.g. the LED)
+    /// are skipped. A clean reboot leaves the safe mode.
+    pub safe_mode: bool,
+}
+
+impl BootInfo {
+    /// Collect the boot diagnostics.
+    ///
+    /// This function should be called once, early during startup. It also marks the current
+    /// session as running.
+    pub fn collect() -> Self {
+        // SAFETY: the marker is only accessed from the main core, there are no concurrent
+        // accesses.
+        let unclean_shutdown = unsafe { SESSION_MARKER } == SESSION_RUNNING;
+        unsafe { SESSION_MARKER = SESSION_RUNNING };
+
+        let reset_reason = reset_reason(Cpu::ProCpu);
+        let power_on = reset_reason == Some(SocResetReason::ChipPowerOn);
+        let unclean_streak = if unclean_shutdown && !power_on {
+            // SAFETY: see above
+            unsafe { UNCLEAN_STREAK }.saturating_add(1)
+        } else {
+            0
+        };
+        unsafe { UNCLEAN_STREAK = unclean_streak };
+
+        let info = Self {
+            reset_reason,
+            wakeup_cause: wakeup_cause(),
+            unclean_shutdown,
+            unclean_streak,
+            safe_mode: unclean_streak >= SAFE_MODE_THRESHOLD,
+        };
+
+        if info.brownout() {
+            defmt::warn!("Reset caused by a brownout");
+        }
+        if info.unclean_shutdown {
+            defmt::warn!("Previous session did not end cleanly");
+        }
+        if info.safe_mode {
+            defmt::warn!(
+                "{} sessions in a row did not end cleanly, booting in the safe mode",
+                info.unclean_streak
+            );
+        }
+
+        info
+    }
+
+    /// Check whether the last reset was caused by the brownout detector.
+    pub fn brownout(&self) -> bool {
+        self.reset_reason == Some(SocResetReason::SysBrownOut)
+    }
+}
+
+/// Mark the current session as ended cleanly.
+///
+/// Call this function right before an intentional reset.
+pub fn mark_clean_shutdown() {
+    // SAFETY: see `BootInfo::collect()`
+    unsafe {
+        SESSION_MARKER = 0;
+        UNCLEAN_STREAK = 0;
+    }
+}
+
+/// Mark the current session as stable, so that a crash later on does not count towards the safe
+/// mode together with the crashes of the previous sessions.
+///
+/// Call this function once the firmware has been running for a while.
+pub fn mark_stable() {
+    // SAFETY: see `BootInfo::collect()`
+    unsafe { UNCLEAN_STREAK = 0 };
+}
@@ -0,0 +1,121 @@
+//! A simple tone generator for a passive buzzer using the LEDC peripheral.
+
+use embassy_time::Timer;
+use esp_hal::{
+    gpio::{AnyPin, DriveMode, OutputPin, Pin},
+    ledc::{
+        LSGlobalClkSource,
+        Ledc,
+        LowSpeed,
+        channel::{self, Channel, ChannelIFace},
+        timer::{self, LSClockSource, TimerIFace},
+    },
+    peripherals::LEDC,
+    time::Rate,
+};
+
+/// Frequency of the tone played by [`Buzzer::beep`] in Hz.
+const BEEP_FREQUENCY: u32 = 2000;
+
+/// Duration of the tone played by [`Buzzer::beep`] in milliseconds.
+const BEEP_DURATION: u32 = 100;
+
+/// A melody to play when an error occurs.
+pub const ERROR: &[Note] = &[Note::new(880, 150), Note::rest(50), Note::new(440, 300)];
+
+/// A melody to play when an operation (e.g. a transfer) has completed successfully.
+pub const SUCCESS: &[Note] = &[Note::new(1047, 100), Note::new(1319, 100), Note::new(1568, 150)];
+
+/// A single note played by the [`Buzzer`].
+#[derive(defmt::Format, Clone, Copy)]
+pub struct Note {
+    /// Frequency of the tone in Hz. A frequency of `0` denotes a rest.
+    pub frequency: u32,
+    /// Duration of the note in milliseconds.
+    pub duration: u32,
+}
+
+impl Note {
+    /// Construct a new [`Note`].
+    pub const fn new(frequency: u32, duration: u32) -> Self {
+        Self {
+            frequency,
+            duration,
+        }
+    }
+
+    /// Construct a rest (silence) lasting `duration` milliseconds.
+    pub const fn rest(duration: u32) -> Self {
+        Self::new(0, duration)
+    }
+}
+
+/// A passive buzzer driver.
+///
+/// Each tone is generated by configuring LEDC timer 0 to the tone's frequency and driving the
+/// output pin from LEDC channel 0 with a 50% duty cycle.
+///
+/// Just like with the LED driver, the buzzer is not critical, so all errors are handled for by
+/// emitting a warning message.
+pub struct Buzzer<'d> {
+    ledc: Ledc<'d>,
+    pin: AnyPin<'d>,
+}
+
+impl<'d> Buzzer<'d> {
+    /// Construct a new [`Buzzer`] from the LEDC peripheral and an output pin.
+    pub fn new(ledc: LEDC<'d>, pin: impl OutputPin + 'd) -> Self {
+        let mut ledc = Ledc::new(ledc);
+        ledc.set_global_slow_clock(LSGlobalClkSource::APBClk);
+
+        Self {
+            ledc,
+            pin: pin.degrade(),
+        }
+    }
+
+    /// Play a short beep.
+    pub async fn beep(&mut self) {
+        self.play(&[Note::new(BEEP_FREQUENCY, BEEP_DURATION)]).await;
+    }
+
+    /// Play a sequence of notes.
+    ///
+    /// If a note cannot be played, a warning log message is emitted and the note is replaced with
+    /// a rest, so that the timing of the sequence is kept.
+    pub async fn play(&mut self, notes: &[Note]) {
+        for note in notes {
+            if note.frequency == 0 {
+                Timer::after_millis(note.duration.into()).await;
+                continue;
+            }
+
+            let mut timer = self.ledc.timer::<LowSpeed>(timer::Number::Timer0);
+            if let Err(e) = timer.configure(timer::config::Config {
+                duty: timer::config::Duty::Duty10Bit,
+                clock_source: LSClockSource::APBClk,
+                frequency: Rate::from_hz(note.frequency),
+            }) {
+                defmt::warn!("Failed to play {:?}: {}", note, e);
+                Timer::after_millis(note.duration.into()).await;
+                continue;
+            }
+
+            let mut channel = Channel::new(channel::Number::Channel0, self.pin.reborrow());
+            if let Err(e) = channel.configure(channel::config::Config {
+                timer: &timer,
+                duty_pct: 50,
+                drive_mode: DriveMode::PushPull,
+            }) {
+                defmt::warn!("Failed to play {:?}: {}", note, e);
+            }
+
+            Timer::after_millis(note.duration.into()).await;
+
+            // silence the buzzer before releasing the channel
+            if let Err(e) = channel.set_duty(0) {
+                defmt::warn!("Failed to silence the buzzer: {}", e);
+            }
+        }
+    }
+}
@@ -0,0 +1,129 @@
+//! Communication between the console reader and the command executor.
+//!
+//! The reader owns the UART and the [`juk_com::Interface`]. It forwards the recieved [`Input`]
+//! to the executor through [`INPUT`] and keeps handling the input while a command runs, so that
+//! the command can be cancelled with [`CANCEL`]. The executor has no direct access to the UART,
+//! it sends [`Request`]s to the reader through [`REQUESTS`] instead.
+
+use alloc::{format, string::String, vec::Vec};
+use core::convert::Infallible;
+
+use embassy_sync::{
+    blocking_mutex::raw::CriticalSectionRawMutex,
+    channel::Channel,
+    signal::Signal,
+};
+use embassy_time::Duration;
+use juk_com::{BellStyle, Input, Picker, Prompt, Terminal};
+
+use crate::memory::Pressure;
+
+/// Number of input events which can be queued for the executor.
+const INPUT_QUEUE_SIZE: usize = 4;
+
+/// Number of requests which can be queued for the reader.
+const REQUEST_QUEUE_SIZE: usize = 8;
+
+/// Input events forwarded from the reader to the executor.
+pub static INPUT: Channel<CriticalSectionRawMutex, Input, INPUT_QUEUE_SIZE> = Channel::new();
+
+/// Requests sent from the executor to the reader.
+pub static REQUESTS: Channel<CriticalSectionRawMutex, Request, REQUEST_QUEUE_SIZE> = Channel::new();
+
+/// Answers to [`Request::Prompt`] sent from the reader to the executor.
+pub static ANSWERS: Channel<CriticalSectionRawMutex, String, 1> = Channel::new();
+
+/// Answers to [`Request::Pick`] sent from the reader to the executor.
+pub static PICKS: Channel<CriticalSectionRawMutex, Option<String>, 1> = Channel::new();
+
+/// Signalled by the reader when CTRL + C is pressed, cancels the active command.
+pub static CANCEL: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+
+/// Signalled by the reader when the console becomes idle (`true`) or active again (`false`), see
+/// [`Request::SetIdleTimeout`].
+pub static IDLE: Signal<CriticalSectionRawMutex, bool> = Signal::new();
+
+/// Signalled by the reader whenever bytes are received on the console, see
+/// [`crate::power::PowerManager::activity`].
+pub static ACTIVITY: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+
+/// A request sent from the executor to the reader.
+pub enum Request {
+    /// Write the payload to the console.
+    Write(Vec<u8>),
+    /// Write a binary frame to the console, bypassing the escape sequence filter of the plain
+    /// mode.
+    WriteFrame(Vec<u8>),
+    /// Change the baud rate of the console.
+    SetBaudrate(u32),
+    /// Enable or disable the plain (ANSI-free) console mode.
+    SetPlain(bool),
+    /// Select how the console signals rejected input.
+    SetBell(BellStyle),
+    /// Signal rejected input, e.g. an unknown command.
+    Bell,
+    /// Set the console inactivity period after which [`IDLE`] is signalled, `None` disables it.
+    SetIdleTimeout(Option<Duration>),
+    /// Read a line of input in a nested prompt context, the answer is sent through [`ANSWERS`].
+    Prompt(Prompt),
+    /// Let the user pick an item from a list, the answer is sent through [`PICKS`].
+    Pick(Picker),
+    /// The memory pressure has changed, free what can be freed.
+    MemoryPressure(Pressure),
+    /// Reboot the chip.
+    Reboot,
+    /// The command has finished (or has been cancelled), the command line is given back to be
+    /// recycled by the [`juk_com::Interface`].
+    Done(String),
+}
+
+/// Ask the user for a line of input in a nested prompt context.
+///
+/// The line being edited in the main context is kept aside until the answer is entered. Pressing
+/// CTRL + C cancels the whole command through [`CANCEL`].
+pub async fn prompt_line(prompt: Prompt) -> String {
+    // a command cancelled while prompting may have left an answer behind
+    ANSWERS.clear();
+    REQUESTS.send(Request::Prompt(prompt)).await;
+    ANSWERS.receive().await
+}
+
+/// Let the user pick an item from the list of `picker`, `None` if the picker is dismissed.
+///
+/// Pressing CTRL + C cancels the whole command through [`CANCEL`].
+pub async fn pick(picker: Picker) -> Option<String> {
+    // a command cancelled while picking may have left an answer behind
+    PICKS.clear();
+    REQUESTS.send(Request::Pick(picker)).await;
+    PICKS.receive().await
+}
+
+/// Ask the user a yes/no `question`, anything other than `y` or `yes` is a no.
+pub async fn confirm(question: &str) -> bool {
+    let answer = prompt_line(Prompt::new(format!("{question} [y/N] "))).await;
+    answer.eq_ignore_ascii_case("y") || answer.eq_ignore_ascii_case("yes")
+}
+
+/// The [`Terminal`] used by the executor.
+///
+/// The output is forwarded to the reader with [`Request::Write`]. The executor recieves its input
+/// through [`INPUT`], so reading from this terminal never completes.
+pub struct ConsoleOutput;
+
+impl Terminal for ConsoleOutput {
+    type Error = Infallible;
+
+    async fn read_byte(&mut self) -> Result<u8, Self::Error> {
+        core::future::pending().await
+    }
+
+    async fn write(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        REQUESTS.send(Request::Write(buf.to_vec())).await;
+        Ok(())
+    }
+
+    /// The writes are performed by the reader, there is nothing to flush here.
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
@@ -1,3 +1,15 @@
 #![no_std]
 
+extern crate alloc;
+
+pub mod bootinfo;
+pub mod buzzer;
+pub mod console;
+pub mod memory;
+pub mod metrics;
+pub mod porcelain;
+pub mod power;
+pub mod sensors;
+pub mod shutdown;
 pub mod strings;
+pub mod supply;
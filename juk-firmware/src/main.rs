@@ -8,66 +8,391 @@
 
 extern crate alloc;
 
+use alloc::{format, string::String, vec::Vec};
+
 use embassy_executor::Spawner;
+use embassy_futures::select::{Either, Either3, Either4, select, select3, select4};
+use embassy_time::{Duration, Timer};
 use esp_backtrace as _;
 use esp_hal::{
+    Async,
+    gpio::{self, InputConfig, Pull},
+    i2c::{self, master::I2c},
+    peripherals::GPIO1,
+    rmt::Rmt,
+    rtc_cntl::Rtc,
+    time::Rate,
     timer::timg::TimerGroup,
     uart::{Config, DataBits, Parity, StopBits, Uart},
 };
 use esp_println as _;
-use juk_com::{Input, Interface, Terminal};
-use juk_firmware::strings;
+use juk_com::{
+    BellStyle,
+    Input,
+    Interface,
+    Picker,
+    PlainTerminal,
+    Terminal,
+    cobs,
+    handshake::{Hello, PROTOCOL_VERSION, Version},
+    router::{self, Router},
+};
+use juk_firmware::{
+    bootinfo::{self, BootInfo},
+    buzzer::{self, Buzzer},
+    console::{
+        self,
+        ACTIVITY,
+        ANSWERS,
+        CANCEL,
+        ConsoleOutput,
+        IDLE,
+        INPUT,
+        PICKS,
+        REQUESTS,
+        Request,
+    },
+    memory::{self, Pressure},
+    metrics::CommandStats,
+    porcelain::{self, OutputFormat},
+    power::{PowerManager, PowerMode},
+    sensors::{self, Sht3x},
+    shutdown::{self, ShutdownReason},
+    strings::{self, MESSAGES},
+    supply::{self, Divider, SupplyEvent, SupplyMonitor},
+};
+use juk_led::{ColorCorrection, ColorOrder, Gamma, LEDAdapter, RGB, colors, parse_color};
 
 esp_bootloader_esp_idf::esp_app_desc!();
 
+/// Time to wait after a baud rate change before printing the confirmation, in milliseconds.
+///
+/// Gives the host time to reconfigure its serial port.
+const BAUDRATE_GRACE_PERIOD: u64 = 500;
+
+/// Number of history entries kept when the memory runs low.
+const LOW_MEMORY_HISTORY: usize = 4;
+
+/// The longest time a command can run before it is dropped.
+///
+/// Long enough to answer a confirmation, short enough to recover from a command stuck on
+/// unresponsive hardware without a reboot.
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The longest decoded binary frame accepted by the executor.
+const MAX_FRAME: u16 = 512;
+
+/// The names of the gamma corrections, offered by `led gamma` without an argument.
+const GAMMA_NAMES: [&str; 3] = ["off", "2.2", "2.8"];
+
+/// The names of the color byte orders, offered by `led order` without an argument.
+const ORDER_NAMES: [&str; 6] = ["grb", "rgb", "brg", "rbg", "gbr", "bgr"];
+
+/// The names of the white point corrections, offered by `led correction` without an argument.
+const CORRECTION_NAMES: [&str; 3] = ["none", "smd5050", "pixel8mm"];
+
+/// The names of the power management policies, offered by `power` without an argument.
+const POWER_NAMES: [&str; 3] = ["auto", "on", "off"];
+
+/// Time the executor waits for work before letting the power manager enter light sleep.
+const POWER_POLL: Duration = Duration::from_millis(100);
+
+/// Uptime after which the session is considered stable, see [`bootinfo::mark_stable`].
+const STABLE_UPTIME: Duration = Duration::from_secs(60);
+
+/// The resistor divider between the supply and the ADC pin, halving the voltage.
+const SUPPLY_DIVIDER: Divider = Divider {
+    top: 100_000,
+    bottom: 100_000,
+};
+
+/// The color shown on the LED while the supply voltage is low.
+const LOW_SUPPLY_COLOR: RGB = colors::RED;
+
+/// The console terminal, ANSI escape sequences can be disabled with the `plain` command.
+type Console = PlainTerminal<Uart<'static, Async>>;
+
+/// The peripherals driven by the console commands, owned by the [`executor`].
+///
+/// The safe mode brings up only the console, so the devices are `None` in the safe mode.
+struct Devices {
+    led: Option<LEDAdapter<'static, Async>>,
+    buzzer: Option<Buzzer<'static>>,
+    power: Option<PowerManager<'static>>,
+}
+
 #[esp_rtos::main]
 async fn main(spawner: Spawner) -> ! {
     let config = esp_hal::Config::default();
     let peripherals = esp_hal::init(config);
+    let boot_info = BootInfo::collect();
 
     esp_alloc::psram_allocator!(peripherals.PSRAM, esp_hal::psram);
 
     let timg0 = TimerGroup::new(peripherals.TIMG0);
     esp_rtos::start(timg0.timer0);
 
-    // TODO: Spawn some tasks
-    let _ = spawner;
-
     let uart_config = Config::default()
         .with_baudrate(115200)
         .with_data_bits(DataBits::_8)
         .with_stop_bits(StopBits::_1)
         .with_parity(Parity::None);
 
-    let mut uart = defmt::expect!(
+    let uart = defmt::expect!(
         Uart::new(peripherals.UART0, uart_config),
         "Failed to initialize the UART interface"
     )
     .into_async();
+    let mut uart = PlainTerminal::new(uart);
 
-    let mut interface = Interface::new();
+    // quiet boot strap, held low to suppress the banners for hosts parsing the console output
+    let quiet_strap = gpio::Input::new(
+        peripherals.GPIO4,
+        InputConfig::default().with_pull(Pull::Up),
+    );
+    if quiet_strap.is_low() {
+        defmt::info!("Quiet boot strap held, skipping the banners");
+    } else {
+        defmt::expect!(strings::print_verinfo(&mut uart).await, "UART write failed");
+        defmt::expect!(
+            strings::print_bootinfo(&mut uart, &boot_info).await,
+            "UART write failed"
+        );
+        defmt::expect!(strings::print_motd(&mut uart).await, "UART write failed");
+    }
+    drop(quiet_strap);
+
+    // the safe mode brings up only the console
+    let led = (!boot_info.safe_mode).then(|| {
+        let rmt = defmt::expect!(
+            Rmt::new(peripherals.RMT, Rate::from_mhz(80)),
+            "Failed to initialize the RMT peripheral"
+        )
+        .into_async();
+        LEDAdapter::new(rmt.channel0, peripherals.GPIO38)
+    });
+    // a passive buzzer driven by the LEDC
+    let buzzer = (!boot_info.safe_mode).then(|| Buzzer::new(peripherals.LEDC, peripherals.GPIO5));
+    let power = (!boot_info.safe_mode).then(|| PowerManager::new(Rtc::new(peripherals.LPWR)));
+    let devices = Devices { led, buzzer, power };
+
+    if !boot_info.safe_mode {
+        let i2c = defmt::expect!(
+            I2c::new(peripherals.I2C0, i2c::master::Config::default()),
+            "Failed to initialize the I2C bus"
+        )
+        .with_sda(peripherals.GPIO8)
+        .with_scl(peripherals.GPIO9)
+        .into_async();
+        defmt::expect!(
+            spawner.spawn(sensors::sampler(Sht3x::new(i2c))),
+            "Failed to spawn the sensor sampler task"
+        );
+
+        let monitor = SupplyMonitor::new(peripherals.ADC1, peripherals.GPIO1, SUPPLY_DIVIDER);
+        defmt::expect!(
+            spawner.spawn(supply_monitor(monitor)),
+            "Failed to spawn the supply monitor task"
+        );
+    }
 
-    defmt::expect!(strings::print_verinfo(&mut uart).await, "UART write failed");
-    uwrite(&mut uart, strings::WELCOME_MOTD).await;
-    uwrite(&mut uart, "$ ").await;
+    defmt::expect!(
+        spawner.spawn(executor(boot_info, devices)),
+        "Failed to spawn the executor task"
+    );
+    defmt::expect!(
+        spawner.spawn(memory::watchdog()),
+        "Failed to spawn the memory watchdog task"
+    );
+    defmt::expect!(
+        spawner.spawn(stability_timer()),
+        "Failed to spawn the stability timer task"
+    );
 
+    reader(uart, uart_config).await
+}
+
+/// The console reader.
+///
+/// Handles the input on the console and forwards it to the [`executor`]. Performs the
+/// [`Request`]s of the executor in between the input events, so the console stays responsive
+/// while a command runs.
+async fn reader(mut uart: Console, mut uart_config: Config) -> ! {
+    let mut interface = Interface::new();
+    interface.set_notices(strings::TEXT_MODE_NOTICE, strings::BINARY_MODE_NOTICE);
+    interface.set_activity_hook(|| ACTIVITY.signal(()));
+    // number of commands forwarded to the executor which have not finished yet
+    let mut pending = 0usize;
+    // the terminal capabilities are probed once the first command is entered, so that a terminal
+    // connected after the boot is probed as well
+    let mut probed = false;
+    // the executor waits for an answer in a nested prompt context, see `console::prompt_line()`
+    let mut prompting = false;
+    // the executor waits for an item of a picker, see `console::pick()`
+    let mut picking = false;
+
+    uwrite(&mut uart, interface.prompt().text()).await;
     loop {
-        match interface.get_input(&mut uart).await {
-            Ok(input) => match input {
-                Input::Binary(items) => defmt::info!("Binary input: {=[u8]}", &items[..]),
-                Input::Text(text) => {
-                    defmt::info!("Text input: {}", text.as_str());
-                    uwrite(&mut uart, "$ ").await;
+        match interface.get_input_or(&mut uart, REQUESTS.receive()).await {
+            Ok(Either::First(input)) => match input {
+                Input::Text(text) if prompting => {
+                    prompting = false;
+                    interface.pop_prompt();
+                    if ANSWERS.try_send(text).is_err() {
+                        defmt::warn!("Prompt answer dropped");
+                    }
+                }
+                Input::EndOfText if prompting => {
+                    defmt::info!("CTRL + C: cancelling the prompting command");
+                    prompting = false;
+                    interface.pop_prompt();
+                    CANCEL.signal(());
+                }
+                Input::Picked(item) if picking => {
+                    picking = false;
+                    if PICKS.try_send(item).is_err() {
+                        defmt::warn!("Picked item dropped");
+                    }
+                }
+                Input::EndOfText if picking => {
+                    defmt::info!("CTRL + C: cancelling the picking command");
+                    picking = false;
+                    CANCEL.signal(());
+                }
+                Input::Binary(_) | Input::Text(_) => {
+                    let is_command = matches!(input, Input::Text(_));
+                    if is_command && !probed {
+                        probed = true;
+                        defmt::expect!(interface.probe(&mut uart).await, "UART write failed");
+                    }
+                    match INPUT.try_send(input) {
+                        Ok(()) if is_command => pending += 1,
+                        Ok(()) => (),
+                        Err(_) => {
+                            defmt::warn!("Executor is busy, input dropped");
+                            uwrite(
+                                &mut uart,
+                                &format!("{} {}\r\n", strings::WARN, MESSAGES.busy),
+                            )
+                            .await;
+                            if pending == 0 {
+                                uwrite(&mut uart, interface.prompt().text()).await;
+                            }
+                        }
+                    }
+                }
+                Input::EndOfText if pending > 0 => {
+                    defmt::info!("CTRL + C: cancelling the active command");
+                    CANCEL.signal(());
+                }
+                Input::Overrun => {
+                    let warning = format!("\r\n{} {}\r\n", strings::WARN, MESSAGES.input_flood);
+                    uwrite(&mut uart, &warning).await;
+                    uwrite(&mut uart, interface.prompt().text()).await;
+                    defmt::expect!(interface.redraw_line(&mut uart).await, "UART write failed");
+                }
+                Input::Idle => IDLE.signal(true),
+                Input::Active => IDLE.signal(false),
+                Input::Capabilities(capabilities) => {
+                    if !capabilities.ansi && !uart.plain() {
+                        defmt::info!("No ANSI support detected, enabling the plain console mode");
+                        uart.set_plain(true);
+                    }
                 }
                 Input::EndOfTransmission => {
                     defmt::info!("CTRL + D: resetting...");
-                    esp_hal::system::software_reset();
+                    defmt::expect!(uart.flush().await, "UART flush failed");
+                    shutdown::reset(ShutdownReason::EndOfTransmission).await;
                 }
                 _ => {
-                    uwrite(&mut uart, "$ ").await;
+                    uwrite(&mut uart, interface.prompt().text()).await;
                     defmt::expect!(interface.redraw_line(&mut uart).await, "UART write failed");
                 }
             },
+            Ok(Either::Second(request)) => match request {
+                Request::Write(bytes) => {
+                    defmt::expect!(uart.write(&bytes).await, "UART write failed")
+                }
+                Request::WriteFrame(bytes) => {
+                    defmt::expect!(uart.inner_mut().write(&bytes).await, "UART write failed")
+                }
+                Request::SetBaudrate(rate) => {
+                    set_baudrate(uart.inner_mut(), &mut uart_config, rate).await
+                }
+                Request::SetPlain(plain) => {
+                    uart.set_plain(plain);
+                    defmt::info!("Plain console mode: {}", plain);
+                }
+                Request::SetBell(bell) => {
+                    interface.set_bell(bell);
+                    defmt::info!("Console bell: {}", bell);
+                }
+                Request::SetIdleTimeout(timeout) => {
+                    interface.set_idle_timeout(timeout);
+                    defmt::info!("Console idle timeout: {:?}", timeout.map(|t| t.as_secs()));
+                }
+                Request::Bell => {
+                    defmt::expect!(interface.bell(&mut uart).await, "UART write failed")
+                }
+                Request::Prompt(prompt) => {
+                    interface.push_prompt(prompt);
+                    uwrite(&mut uart, interface.prompt().text()).await;
+                    prompting = true;
+                }
+                Request::Pick(picker) => {
+                    defmt::expect!(
+                        interface.start_picker(picker, &mut uart).await,
+                        "UART write failed"
+                    );
+                    picking = true;
+                }
+                Request::MemoryPressure(pressure) => {
+                    let warning = match pressure {
+                        Pressure::Normal => None,
+                        Pressure::Low => {
+                            interface.release_memory(LOW_MEMORY_HISTORY);
+                            Some(MESSAGES.memory_low)
+                        }
+                        Pressure::Critical => {
+                            interface.release_memory(0);
+                            Some(MESSAGES.memory_critical)
+                        }
+                    };
+                    if let Some(warning) = warning {
+                        let warning = format!("\r\n{} {warning}\r\n", strings::WARN);
+                        uwrite(&mut uart, &warning).await;
+                        uwrite(&mut uart, interface.prompt().text()).await;
+                        defmt::expect!(interface.redraw_line(&mut uart).await, "UART write failed");
+                    }
+                }
+                Request::Reboot => {
+                    uwrite(
+                        &mut uart,
+                        &format!("{} {}\r\n", strings::INFO, MESSAGES.rebooting),
+                    )
+                    .await;
+                    defmt::expect!(uart.flush().await, "UART flush failed");
+                    shutdown::reset(ShutdownReason::Reboot).await;
+                }
+                Request::Done(text) => {
+                    interface.recycle(text);
+                    // the command could have timed out while waiting for an answer
+                    if prompting {
+                        prompting = false;
+                        interface.pop_prompt();
+                    }
+                    if picking {
+                        picking = false;
+                        interface.close_picker();
+                    }
+                    pending = pending.saturating_sub(1);
+                    if pending == 0 {
+                        // the user may have typed ahead while the command was running
+                        uwrite(&mut uart, interface.prompt().text()).await;
+                        defmt::expect!(interface.redraw_line(&mut uart).await, "UART write failed");
+                    }
+                }
+            },
             Err(e) => {
                 defmt::error!("UART Error: {}", e);
                 defmt::panic!();
@@ -76,6 +401,751 @@ async fn main(spawner: Spawner) -> ! {
     }
 }
 
+/// Mark the session as stable once it has been running for [`STABLE_UPTIME`].
+#[embassy_executor::task]
+async fn stability_timer() {
+    Timer::after(STABLE_UPTIME).await;
+    defmt::debug!("Session stable, clearing the crash streak");
+    bootinfo::mark_stable();
+}
+
+/// Monitor the supply voltage on GPIO1, see [`supply::monitor`].
+#[embassy_executor::task]
+async fn supply_monitor(monitor: SupplyMonitor<'static, GPIO1<'static>>) {
+    supply::monitor(monitor).await
+}
+
+/// The command executor task, see [`serve`].
+///
+/// Holds a [`shutdown::Listener`]: once a reset is requested, the executor stops serving, parks
+/// the LED (turns it off) and lets the reset proceed.
+#[embassy_executor::task]
+async fn executor(boot_info: BootInfo, mut devices: Devices) {
+    let mut listener = defmt::expect!(
+        shutdown::Listener::new(),
+        "Failed to register the executor as a shutdown listener"
+    );
+    let reason = match select(listener.wait(), serve(&boot_info, &mut devices)).await {
+        Either::First(reason) => reason,
+        Either::Second(never) => never,
+    };
+
+    defmt::info!("Parking the LED before the reset: {}", reason);
+    if let Some(led) = devices.led.as_mut() {
+        // a transition could outlast the shutdown deadline
+        led.set_transition(Duration::MIN);
+        led.force_color(colors::BLACK).await;
+    }
+    drop(listener);
+    core::future::pending().await
+}
+
+/// Serve the commands and the background events until the executor is stopped.
+///
+/// Runs the commands forwarded by the [`reader`] one at a time. The active command is dropped
+/// when [`CANCEL`] is signalled or when it runs longer than [`COMMAND_TIMEOUT`]. While there is
+/// no work for [`POWER_POLL`], the power manager may put the chip into light sleep.
+async fn serve(boot_info: &BootInfo, devices: &mut Devices) -> ! {
+    let mut output = ConsoleOutput;
+    let mut session = Session::new();
+    let mut supply_warning = SupplyWarning::new();
+
+    loop {
+        let work = select4(
+            INPUT.receive(),
+            IDLE.wait(),
+            supply::EVENTS.wait(),
+            Timer::after(POWER_POLL),
+        );
+        let input = match work.await {
+            Either4::First(input) => input,
+            Either4::Second(idle) => {
+                // the warning stays visible while the console is idle
+                match devices.led.as_mut() {
+                    Some(led) if !supply_warning.is_active() => {
+                        session.idle_dim.apply(led, idle).await
+                    }
+                    _ => (),
+                }
+                continue;
+            }
+            Either4::Third(event) => {
+                if let Some(led) = devices.led.as_mut() {
+                    supply_warning.apply(led, event).await;
+                }
+                continue;
+            }
+            Either4::Fourth(()) => {
+                if let Some(power) = devices.power.as_mut() {
+                    if ACTIVITY.try_take().is_some() {
+                        power.activity();
+                    }
+                    power.idle();
+                }
+                continue;
+            }
+        };
+        match input {
+            Input::Binary(items) => {
+                defmt::info!("Binary input: {=[u8]}", &items[..]);
+                run_frame(&session.router, &items, &mut session.last_message).await;
+            }
+            Input::Text(text) => {
+                defmt::info!("Text input: {}", text.as_str());
+                // a CTRL + C pressed while no command was running does not apply to this one
+                CANCEL.reset();
+                session.command_stats.record(&text);
+                let command = run_command(&mut output, &mut session, &text, boot_info, devices);
+                let deadline = Timer::after(COMMAND_TIMEOUT);
+                match select3(command, CANCEL.wait(), deadline).await {
+                    Either3::First(()) => (),
+                    Either3::Second(()) => defmt::info!("Command cancelled: {}", text.as_str()),
+                    Either3::Third(()) => {
+                        defmt::warn!("Command timed out: {}", text.as_str());
+                        let warning = format!("\r\n{} {}\r\n", strings::WARN, MESSAGES.timed_out);
+                        uwrite(&mut output, &warning).await;
+                    }
+                }
+                REQUESTS.send(Request::Done(text)).await;
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Handle a single COBS encoded binary frame, including the sentinel.
+///
+/// The decoded message is saved to `last_message` and passed to its handler by `router`.
+async fn run_frame(router: &Router, frame: &[u8], last_message: &mut Vec<u8>) {
+    let mut buf = [0; MAX_FRAME as usize];
+    let len = match cobs::decode_into(&frame[..frame.len() - 1], &mut buf) {
+        Ok(len) => len,
+        Err(e) => {
+            defmt::warn!("Invalid binary frame: {}", e);
+            return;
+        }
+    };
+
+    last_message.clear();
+    last_message.extend_from_slice(&buf[..len]);
+    if let Some(reply) = route_message(router, &buf[..len]) {
+        let mut reply = cobs::encode(&reply);
+        reply.push(cobs::SENTINEL);
+        REQUESTS.send(Request::WriteFrame(reply)).await;
+    }
+}
+
+/// Pass a decoded binary protocol message to its handler, returns the reply message if there is
+/// one.
+fn route_message(router: &Router, message: &[u8]) -> Option<Vec<u8>> {
+    router
+        .route(message)
+        .inspect_err(|e| defmt::warn!("Binary message not handled: {}", e))
+        .ok()
+        .flatten()
+}
+
+/// Handle a message sent to the [`router::CONTROL`] endpoint.
+fn control_message(message: &[u8]) -> Option<Vec<u8>> {
+    if let Some(hello) = Hello::from_bytes(message) {
+        defmt::info!("Binary handshake, host protocol: {}", hello.protocol);
+        let version = Version {
+            protocol: PROTOCOL_VERSION,
+            features: 0,
+            max_frame: MAX_FRAME,
+        };
+        return Some(version.to_bytes().to_vec());
+    }
+
+    defmt::warn!("Unknown control message: {=[u8]}", message);
+    None
+}
+
+/// Parse and run a single command line.
+async fn run_command(
+    output: &mut ConsoleOutput,
+    session: &mut Session,
+    line: &str,
+    boot_info: &BootInfo,
+    devices: &mut Devices,
+) {
+    let Session {
+        format,
+        last_message,
+        router,
+        idle_dim,
+        command_stats,
+    } = session;
+    let mut args = line.split_whitespace();
+    match args.next() {
+        Some("bootinfo") => match format {
+            OutputFormat::Text => defmt::unwrap!(strings::print_bootinfo(output, boot_info).await),
+            OutputFormat::Json => {
+                defmt::unwrap!(porcelain::print_bootinfo(output, boot_info).await)
+            }
+        },
+        Some("baud") => match args.next().and_then(|arg| arg.parse().ok()) {
+            Some(rate) => {
+                // the answer has to be sent at the old baud rate
+                done(output, *format).await;
+                REQUESTS.send(Request::SetBaudrate(rate)).await
+            }
+            None => usage(output, *format, "baud <rate>").await,
+        },
+        Some("version") => match format {
+            OutputFormat::Text => defmt::unwrap!(strings::print_verinfo(output).await),
+            OutputFormat::Json => defmt::unwrap!(porcelain::print_version(output).await),
+        },
+        Some("plain") => match args.next() {
+            Some("on") => {
+                REQUESTS.send(Request::SetPlain(true)).await;
+                done(output, *format).await
+            }
+            Some("off") => {
+                REQUESTS.send(Request::SetPlain(false)).await;
+                done(output, *format).await
+            }
+            _ => usage(output, *format, "plain <on|off>").await,
+        },
+        Some("set") => match args.next() {
+            Some("output") => match args.next().and_then(OutputFormat::from_name) {
+                Some(new_format) => {
+                    defmt::info!("Output format set to {:?}", new_format);
+                    *format = new_format;
+                    done(output, *format).await
+                }
+                None => usage(output, *format, "set output <text|json>").await,
+            },
+            Some("bell") => match args.next().and_then(BellStyle::from_name) {
+                Some(bell) => {
+                    REQUESTS.send(Request::SetBell(bell)).await;
+                    done(output, *format).await
+                }
+                None => usage(output, *format, "set bell <on|visual|off>").await,
+            },
+            _ => {
+                usage(
+                    output,
+                    *format,
+                    "set output <text|json> | set bell <on|visual|off>",
+                )
+                .await
+            }
+        },
+        Some("led") => match devices.led.as_mut() {
+            Some(led) => led_command(output, *format, led, idle_dim, line).await,
+            None => {
+                let error = "led disabled in safe mode";
+                safe_mode(output, *format, MESSAGES.safe_mode_led, error).await
+            }
+        },
+        Some("beep") => match devices.buzzer.as_mut() {
+            Some(buzzer) => match args.next() {
+                None => {
+                    buzzer.beep().await;
+                    done(output, *format).await
+                }
+                Some("error") => {
+                    buzzer.play(buzzer::ERROR).await;
+                    done(output, *format).await
+                }
+                Some("success") => {
+                    buzzer.play(buzzer::SUCCESS).await;
+                    done(output, *format).await
+                }
+                Some(_) => usage(output, *format, "beep [error|success]").await,
+            },
+            None => {
+                let error = "buzzer disabled in safe mode";
+                safe_mode(output, *format, MESSAGES.safe_mode_buzzer, error).await
+            }
+        },
+        Some("stats") => match args.next() {
+            Some("commands") => match format {
+                OutputFormat::Text => {
+                    defmt::unwrap!(strings::print_command_stats(output, command_stats).await)
+                }
+                OutputFormat::Json => {
+                    defmt::unwrap!(porcelain::print_command_stats(output, command_stats).await)
+                }
+            },
+            _ => usage(output, *format, "stats commands").await,
+        },
+        Some("sensor") => match args.next() {
+            Some("list") => {
+                let sample = sensors::READINGS.try_get();
+                match format {
+                    OutputFormat::Text => {
+                        defmt::unwrap!(strings::print_sensors(output, sample).await)
+                    }
+                    OutputFormat::Json => {
+                        defmt::unwrap!(porcelain::print_sensors(output, sample).await)
+                    }
+                }
+            }
+            Some("read") => {
+                let sample = sensors::READINGS.try_get();
+                match format {
+                    OutputFormat::Text => {
+                        defmt::unwrap!(strings::print_reading(output, sample).await)
+                    }
+                    OutputFormat::Json => {
+                        defmt::unwrap!(porcelain::print_reading(output, sample).await)
+                    }
+                }
+            }
+            _ => usage(output, *format, "sensor list | sensor read").await,
+        },
+        Some("battery") => match args.next() {
+            None => {
+                let status = supply::STATUS.try_get();
+                match format {
+                    OutputFormat::Text => {
+                        defmt::unwrap!(strings::print_supply(output, status).await)
+                    }
+                    OutputFormat::Json => {
+                        defmt::unwrap!(porcelain::print_supply(output, status).await)
+                    }
+                }
+            }
+            Some("calibrate") => match args.next().and_then(|arg| arg.parse().ok()) {
+                Some(actual) => {
+                    supply::CALIBRATE.signal(actual);
+                    done(output, *format).await
+                }
+                None => usage(output, *format, "battery calibrate <millivolts>").await,
+            },
+            Some(_) => usage(output, *format, "battery [calibrate <millivolts>]").await,
+        },
+        Some("power") => match devices.power.as_mut() {
+            Some(power) => power_command(output, *format, power, line).await,
+            None => {
+                let error = "power management disabled in safe mode";
+                safe_mode(output, *format, MESSAGES.safe_mode_power, error).await
+            }
+        },
+        Some("frame") => frame_command(output, *format, router, last_message, line).await,
+        Some("reboot") => {
+            // the JSON output is meant for scripts, which cannot answer the confirmation
+            let confirmed = *format == OutputFormat::Json
+                || args.next() == Some("-y")
+                || console::confirm(MESSAGES.reboot_confirm).await;
+            if confirmed {
+                done(output, *format).await;
+                REQUESTS.send(Request::Reboot).await;
+            }
+        }
+        Some(command) => {
+            defmt::info!("Unknown command: {}", command);
+            match format {
+                OutputFormat::Text => {
+                    let warning = format!(
+                        "{} {} {command}\r\n",
+                        strings::WARN,
+                        MESSAGES.unknown_command
+                    );
+                    uwrite(output, &warning).await;
+                    REQUESTS.send(Request::Bell).await
+                }
+                OutputFormat::Json => {
+                    defmt::unwrap!(porcelain::print_error(output, "unknown command").await)
+                }
+            }
+        }
+        None => (),
+    }
+}
+
+/// Run the `frame` command, which exchanges binary protocol messages in the text mode.
+async fn frame_command(
+    output: &mut ConsoleOutput,
+    format: OutputFormat,
+    router: &Router,
+    last_message: &[u8],
+    line: &str,
+) {
+    let mut args = line.split_whitespace().skip(1);
+    let (label, message) = match args.next() {
+        Some("send") => match parse_hex(args) {
+            Some(message) if !message.is_empty() => ("Reply", route_message(router, &message)),
+            _ => return usage(output, format, "frame send <hex...>").await,
+        },
+        Some("recv") => (
+            "Received",
+            (!last_message.is_empty()).then(|| last_message.to_vec()),
+        ),
+        _ => return usage(output, format, "frame send <hex...> | frame recv").await,
+    };
+
+    match format {
+        OutputFormat::Text => {
+            defmt::unwrap!(strings::print_message(output, label, message.as_deref()).await)
+        }
+        OutputFormat::Json => {
+            defmt::unwrap!(porcelain::print_message(output, message.as_deref()).await)
+        }
+    }
+}
+
+/// Parse the bytes given as hex digits, optionally split into several arguments (e.g. `01 02`
+/// or `0102`).
+fn parse_hex<'a>(args: impl Iterator<Item = &'a str>) -> Option<Vec<u8>> {
+    let mut bytes = Vec::new();
+    for arg in args {
+        if arg.len() % 2 != 0 {
+            return None;
+        }
+        for i in (0..arg.len()).step_by(2) {
+            bytes.push(u8::from_str_radix(arg.get(i..i + 2)?, 16).ok()?);
+        }
+    }
+    Some(bytes)
+}
+
+/// The state of the [`executor`] kept between the commands.
+struct Session {
+    /// The output format of the command answers, see the `set output` command.
+    format: OutputFormat,
+    /// The last message received in the binary mode, see the `frame recv` command.
+    last_message: Vec<u8>,
+    router: Router,
+    idle_dim: IdleDim,
+    command_stats: CommandStats,
+}
+
+impl Session {
+    /// Construct a new [`Session`] with the text output and the [`router::CONTROL`] endpoint
+    /// registered.
+    fn new() -> Self {
+        let mut router = Router::new();
+        defmt::unwrap!(router.register(router::CONTROL, control_message));
+        Self {
+            format: OutputFormat::Text,
+            last_message: Vec::new(),
+            router,
+            idle_dim: IdleDim::new(),
+            command_stats: CommandStats::new(),
+        }
+    }
+}
+
+/// Dimming of the LED while the console is idle, see the `led idle-dim` command.
+struct IdleDim {
+    /// Brightness of the dimmed LED in percent, `0` turns it off.
+    level: u8,
+    /// The color restored when the console becomes active, `Some` while the LED is dimmed.
+    saved: Option<RGB>,
+}
+
+impl IdleDim {
+    /// Construct a new [`IdleDim`], turning the LED off.
+    const fn new() -> Self {
+        Self {
+            level: 0,
+            saved: None,
+        }
+    }
+
+    /// Dim the LED when the console becomes `idle`, restore its color when it becomes active.
+    async fn apply(&mut self, led: &mut LEDAdapter<'static, Async>, idle: bool) {
+        match (idle, self.saved) {
+            (true, None) => {
+                let color = led.color();
+                self.saved = Some(color);
+                let level = (self.level as u16 * 255 / 100) as u8;
+                led.set_color(RGB::new(0x00, 0x00, 0x00).lerp(color, level))
+                    .await;
+            }
+            (false, Some(color)) => {
+                self.saved = None;
+                led.set_color(color).await;
+            }
+            _ => (),
+        }
+    }
+}
+
+/// The LED warning shown while the supply voltage is low, see [`SupplyEvent`].
+struct SupplyWarning {
+    /// The color restored when the supply recovers, `Some` while the warning is shown.
+    saved: Option<RGB>,
+}
+
+impl SupplyWarning {
+    /// Construct a new [`SupplyWarning`], not shown.
+    const fn new() -> Self {
+        Self { saved: None }
+    }
+
+    /// Check whether the warning is shown.
+    fn is_active(&self) -> bool {
+        self.saved.is_some()
+    }
+
+    /// Show the warning when the supply voltage drops, restore the color when it recovers.
+    async fn apply(&mut self, led: &mut LEDAdapter<'static, Async>, event: SupplyEvent) {
+        match (event, self.saved) {
+            (SupplyEvent::Low(_), None) => {
+                self.saved = Some(led.color());
+                led.set_color(LOW_SUPPLY_COLOR).await;
+            }
+            (SupplyEvent::Restored(_), Some(color)) => {
+                self.saved = None;
+                led.set_color(color).await;
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Let the user pick one of the setting `names` from a list, `parse` converts a name to the
+/// setting. The name of the `current` setting is selected initially.
+///
+/// Returns `None` if the picker is dismissed.
+async fn pick_setting<S: PartialEq>(
+    title: &str,
+    names: &[&str],
+    parse: fn(&str) -> Option<S>,
+    current: S,
+) -> Option<S> {
+    let selected = names
+        .iter()
+        .position(|name| parse(name).as_ref() == Some(&current))
+        .unwrap_or(0);
+    let items = names.iter().map(|name| String::from(*name)).collect();
+    let title = format!("{} {title} ({})", strings::INFO, MESSAGES.pick_hint);
+    let picker = Picker::new(title, items).with_selected(selected);
+    console::pick(picker).await.as_deref().and_then(parse)
+}
+
+/// Run the `power` command.
+async fn power_command(
+    output: &mut ConsoleOutput,
+    format: OutputFormat,
+    power: &mut PowerManager<'static>,
+    line: &str,
+) {
+    let mode = match line.split_whitespace().nth(1) {
+        Some(arg) => PowerMode::from_name(arg),
+        None if format == OutputFormat::Text => {
+            let title = MESSAGES.pick_power;
+            let current = power.mode();
+            match pick_setting(title, &POWER_NAMES, PowerMode::from_name, current).await {
+                Some(mode) => Some(mode),
+                None => return,
+            }
+        }
+        None => None,
+    };
+    match mode {
+        Some(mode) => {
+            power.set_mode(mode);
+            done(output, format).await
+        }
+        None => usage(output, format, "power <auto|on|off>").await,
+    }
+}
+
+/// Run the `led` command.
+async fn led_command(
+    output: &mut ConsoleOutput,
+    format: OutputFormat,
+    led: &mut LEDAdapter<'static, Async>,
+    idle_dim: &mut IdleDim,
+    line: &str,
+) {
+    let mut args = line.split_whitespace().skip(1);
+    match args.next() {
+        Some("idle-dim") => {
+            let timeout = match args.next() {
+                Some("off") => Some(None),
+                Some(arg) => arg
+                    .parse::<u64>()
+                    .ok()
+                    .map(|minutes| (minutes > 0).then(|| Duration::from_secs(minutes * 60))),
+                None => None,
+            };
+            let level = match args.next() {
+                Some(arg) => arg.parse().ok().filter(|level| *level <= 100),
+                None => Some(0),
+            };
+            match (timeout, level) {
+                (Some(timeout), Some(level)) => {
+                    idle_dim.level = level;
+                    REQUESTS.send(Request::SetIdleTimeout(timeout)).await;
+                    done(output, format).await
+                }
+                _ => usage(output, format, "led idle-dim <minutes|off> [percent]").await,
+            }
+            return;
+        }
+        Some("transition") => {
+            match args.next().and_then(|arg| arg.parse().ok()) {
+                Some(ms) => {
+                    led.set_transition(Duration::from_millis(ms));
+                    done(output, format).await
+                }
+                None => usage(output, format, "led transition <ms>").await,
+            }
+            return;
+        }
+        Some("gamma") => {
+            let gamma = match args.next() {
+                Some(arg) => Gamma::from_name(arg),
+                None if format == OutputFormat::Text => {
+                    let title = MESSAGES.pick_gamma;
+                    let current = led.gamma();
+                    match pick_setting(title, &GAMMA_NAMES, Gamma::from_name, current).await {
+                        Some(gamma) => Some(gamma),
+                        None => return,
+                    }
+                }
+                None => None,
+            };
+            match gamma {
+                Some(gamma) => {
+                    led.set_gamma(gamma);
+                    done(output, format).await
+                }
+                None => usage(output, format, "led gamma <off|2.2|2.8>").await,
+            }
+            return;
+        }
+        Some("order") => {
+            let order = match args.next() {
+                Some(arg) => ColorOrder::from_name(arg),
+                None if format == OutputFormat::Text => {
+                    let title = MESSAGES.pick_order;
+                    let current = led.color_order();
+                    match pick_setting(title, &ORDER_NAMES, ColorOrder::from_name, current).await {
+                        Some(order) => Some(order),
+                        None => return,
+                    }
+                }
+                None => None,
+            };
+            match order {
+                Some(order) => {
+                    led.set_color_order(order);
+                    done(output, format).await
+                }
+                None => usage(output, format, "led order <grb|rgb|brg|rbg|gbr|bgr>").await,
+            }
+            return;
+        }
+        Some("correction") => {
+            let correction = match args.next() {
+                Some(arg) => ColorCorrection::from_name(arg),
+                None if format == OutputFormat::Text => {
+                    let title = MESSAGES.pick_correction;
+                    let current = led.correction();
+                    let parse = ColorCorrection::from_name;
+                    match pick_setting(title, &CORRECTION_NAMES, parse, current).await {
+                        Some(correction) => Some(correction),
+                        None => return,
+                    }
+                }
+                None => None,
+            };
+            match correction {
+                Some(correction) => {
+                    led.set_correction(correction);
+                    done(output, format).await
+                }
+                None => usage(output, format, "led correction <none|smd5050|pixel8mm>").await,
+            }
+            return;
+        }
+        Some("dump") => {
+            match format {
+                OutputFormat::Text => {
+                    defmt::unwrap!(strings::print_led_frame(output, led.last_frame()).await)
+                }
+                OutputFormat::Json => {
+                    defmt::unwrap!(porcelain::print_led_frame(output, led.last_frame()).await)
+                }
+            }
+            return;
+        }
+        Some("stats") => {
+            match args.next() {
+                None => match format {
+                    OutputFormat::Text => {
+                        defmt::unwrap!(strings::print_led_stats(output, &led.stats()).await)
+                    }
+                    OutputFormat::Json => {
+                        defmt::unwrap!(porcelain::print_led_stats(output, &led.stats()).await)
+                    }
+                },
+                Some("reset") => {
+                    led.reset_stats();
+                    done(output, format).await
+                }
+                Some(_) => usage(output, format, "led stats [reset]").await,
+            }
+            return;
+        }
+        _ => (),
+    }
+
+    // the color may contain spaces, e.g. `rgb(255, 128, 0)`
+    let color = line.trim_start()["led".len()..].trim();
+    if color.is_empty() {
+        usage(
+            output,
+            format,
+            "led <color> | led transition <ms> | led gamma <off|2.2|2.8> | led order <grb|rgb|brg|rbg|gbr|bgr> | led correction <none|smd5050|pixel8mm> | led dump | led stats [reset] | led idle-dim <minutes|off> [percent]",
+        )
+        .await;
+        return;
+    }
+    match parse_color(color) {
+        Ok(color) => {
+            led.set_color(&color).await;
+            done(output, format).await
+        }
+        Err(e) => match format {
+            OutputFormat::Text => {
+                uwrite(
+                    output,
+                    &format!("{} {}: {e}\r\n", strings::WARN, MESSAGES.invalid_color),
+                )
+                .await
+            }
+            OutputFormat::Json => {
+                defmt::unwrap!(porcelain::print_error(output, &format!("invalid color: {e}")).await)
+            }
+        },
+    }
+}
+
+/// Print the usage of a command in the active output `format`.
+async fn usage(output: &mut ConsoleOutput, format: OutputFormat, text: &str) {
+    match format {
+        OutputFormat::Text => uwrite(output, &format!("usage: {text}\r\n")).await,
+        OutputFormat::Json => {
+            defmt::unwrap!(porcelain::print_error(output, &format!("usage: {text}")).await)
+        }
+    }
+}
+
+/// Report a command which succeeded without printing anything, so that the scripts using the
+/// JSON output format get an answer to every command.
+async fn done(output: &mut ConsoleOutput, format: OutputFormat) {
+    if format == OutputFormat::Json {
+        defmt::unwrap!(porcelain::print_ok(output).await)
+    }
+}
+
+/// Report a command of a device disabled in the safe mode, `warning` is printed in the text
+/// output format, `error` in the JSON one.
+async fn safe_mode(output: &mut ConsoleOutput, format: OutputFormat, warning: &str, error: &str) {
+    match format {
+        OutputFormat::Text => uwrite(output, &format!("{} {warning}\r\n", strings::WARN)).await,
+        OutputFormat::Json => defmt::unwrap!(porcelain::print_error(output, error).await),
+    }
+}
+
 /// Quick wrapper for UART writes using the [`Terminal`] trait.
 ///
 /// NOTE: for testing purposes only.
@@ -83,3 +1153,42 @@ async fn main(spawner: Spawner) -> ! {
 async fn uwrite<T: Terminal>(term: &mut T, text: &str) {
     defmt::expect!(term.write(text.as_bytes()).await, "UART write failed");
 }
+
+/// Change the baud rate of the console UART to `rate`.
+///
+/// The change is announced at the old rate and confirmed at the new rate after
+/// [`BAUDRATE_GRACE_PERIOD`]. If the rate is not supported, the old configuration is kept.
+async fn set_baudrate(uart: &mut Uart<'_, Async>, config: &mut Config, rate: u32) {
+    let new_config = config.with_baudrate(rate);
+
+    uwrite(
+        uart,
+        &format!("{} {} {rate}\r\n", strings::INFO, MESSAGES.baud_switching),
+    )
+    .await;
+    defmt::expect!(uart.flush_async().await, "UART flush failed");
+
+    if let Err(e) = uart.apply_config(&new_config) {
+        defmt::warn!("Failed to set baud rate to {}: {}", rate, e);
+        defmt::expect!(
+            uart.apply_config(config),
+            "Failed to restore the UART configuration"
+        );
+        uwrite(
+            uart,
+            &format!("{} {} {rate}\r\n", strings::WARN, MESSAGES.baud_unsupported),
+        )
+        .await;
+        return;
+    }
+
+    *config = new_config;
+    defmt::info!("Baud rate set to {}", rate);
+
+    Timer::after_millis(BAUDRATE_GRACE_PERIOD).await;
+    uwrite(
+        uart,
+        &format!("{} {} {rate}\r\n", strings::INFO, MESSAGES.baud_set),
+    )
+    .await;
+}
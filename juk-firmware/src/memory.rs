@@ -0,0 +1,68 @@
+//! Heap watchdog degrading the firmware gracefully when the memory runs low.
+//!
+//! The [`watchdog`] task samples the free heap of [`esp_alloc::HEAP`] and reports the changes of
+//! the [`Pressure`] to the console reader with [`Request::MemoryPressure`], which frees what it
+//! can (e.g. the command history) and warns the user, instead of letting an allocation failure
+//! abort the firmware.
+
+use embassy_time::{Duration, Ticker};
+
+use crate::console::{REQUESTS, Request};
+
+/// Time between two heap samples.
+const CHECK_PERIOD: Duration = Duration::from_secs(1);
+
+/// Free heap in percent below which the memory is low.
+const LOW_PERCENT: usize = 10;
+
+/// Free heap in percent below which the memory is critically low.
+const CRITICAL_PERCENT: usize = 3;
+
+/// The memory pressure, ordered from the lowest.
+#[derive(defmt::Format, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Pressure {
+    /// Enough free memory.
+    Normal,
+    /// Less than [`LOW_PERCENT`] of the heap is free.
+    Low,
+    /// Less than [`CRITICAL_PERCENT`] of the heap is free.
+    Critical,
+}
+
+impl Pressure {
+    /// The current memory pressure.
+    pub fn current() -> Self {
+        let free = esp_alloc::HEAP.free();
+        let total = free + esp_alloc::HEAP.used();
+
+        if free * 100 < total * CRITICAL_PERCENT {
+            Self::Critical
+        } else if free * 100 < total * LOW_PERCENT {
+            Self::Low
+        } else {
+            Self::Normal
+        }
+    }
+}
+
+/// Watch the free heap and report the changes of the [`Pressure`].
+#[embassy_executor::task]
+pub async fn watchdog() {
+    let mut pressure = Pressure::Normal;
+    let mut ticker = Ticker::every(CHECK_PERIOD);
+
+    loop {
+        ticker.next().await;
+
+        let current = Pressure::current();
+        if current != pressure {
+            defmt::info!(
+                "Memory pressure: {} ({} bytes free)",
+                current,
+                esp_alloc::HEAP.free()
+            );
+            pressure = current;
+            REQUESTS.send(Request::MemoryPressure(current)).await;
+        }
+    }
+}
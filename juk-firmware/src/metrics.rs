@@ -0,0 +1,57 @@
+//! Usage metrics of the console commands, see the `stats commands` command.
+
+use embassy_time::Instant;
+
+/// The commands tracked by [`CommandStats`].
+///
+/// Unknown commands are not tracked, so that mistyped input cannot grow the statistics.
+pub const COMMANDS: [&str; 13] = [
+    "battery", "baud", "beep", "bootinfo", "frame", "led", "plain", "power", "reboot", "sensor",
+    "set", "stats", "version",
+];
+
+/// The usage of a single command.
+#[derive(Clone, Copy, Default)]
+pub struct CommandUsage {
+    /// Number of times the command was run.
+    pub count: u32,
+    /// The time of the last run, `None` if the command was never run.
+    pub last_run: Option<Instant>,
+}
+
+/// Invocation counts and last run times of the [`COMMANDS`].
+pub struct CommandStats {
+    usage: [CommandUsage; COMMANDS.len()],
+}
+
+impl CommandStats {
+    /// Construct new [`CommandStats`] with no commands run.
+    pub const fn new() -> Self {
+        Self {
+            usage: [CommandUsage {
+                count: 0,
+                last_run: None,
+            }; COMMANDS.len()],
+        }
+    }
+
+    /// Record a run of the command of `line`, the unknown commands are ignored.
+    pub fn record(&mut self, line: &str) {
+        let Some(name) = line.split_whitespace().next() else {
+            return;
+        };
+        if let Some(usage) = COMMANDS
+            .iter()
+            .position(|&command| command == name)
+            .map(|i| &mut self.usage[i])
+        {
+            usage.count = usage.count.saturating_add(1);
+            usage.last_run = Some(Instant::now());
+        }
+    }
+
+    /// Iterate over the usage of the commands, in the order of [`COMMANDS`].
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, CommandUsage)> {
+        COMMANDS.into_iter().zip(self.usage)
+    }
+}
@@ -0,0 +1,339 @@
+//! Machine readable output of the console commands.
+//!
+//! In the JSON output mode each command prints a single line containing a JSON object, so that
+//! host scripts can drive the text console without parsing the human readable output.
+
+use alloc::{format, string::String};
+
+use juk_com::Terminal;
+use juk_led::{Frame, RGB, Stats};
+use serde::{Serialize, Serializer};
+
+use crate::{
+    bootinfo::BootInfo,
+    metrics::CommandStats,
+    sensors::{Reading, Sample},
+    strings::BUILD_INFO,
+    supply::SupplyStatus,
+};
+
+/// Size of the buffer a single JSON line is serialized into.
+const LINE_BUFFER_SIZE: usize = 512;
+
+/// The output format of the console commands.
+#[derive(defmt::Format, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human readable text.
+    Text,
+    /// Line-delimited JSON.
+    Json,
+}
+
+impl OutputFormat {
+    /// Parse an output format from its name (`text` or `json`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "text" => Some(Self::Text),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+/// The boot diagnostics, see [`BootInfo`].
+#[derive(Serialize)]
+struct BootReport<'a> {
+    reset_reason: Option<&'a str>,
+    wakeup_cause: &'a str,
+    brownout: bool,
+    unclean_shutdown: bool,
+    unclean_streak: u32,
+    safe_mode: bool,
+    heap_used: usize,
+    heap_free: usize,
+}
+
+/// The firmware build information, see [`BUILD_INFO`].
+#[derive(Serialize)]
+struct VersionReport {
+    name: &'static str,
+    version: &'static str,
+    commit: &'static str,
+    branch: &'static str,
+    build_time: &'static str,
+    rustc: &'static str,
+    device: Option<&'static str>,
+    features: Features,
+}
+
+/// The cargo features enabled in [`BUILD_INFO`], serialized as a sequence.
+struct Features;
+
+impl Serialize for Features {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(BUILD_INFO.features())
+    }
+}
+
+/// A frame transmitted to the LED, see [`Frame`].
+#[derive(Serialize)]
+struct FrameReport<'a> {
+    color: &'a str,
+    bits: u32,
+}
+
+/// The LED timing statistics, see [`Stats`], the times are in microseconds.
+#[derive(Serialize)]
+struct StatsReport {
+    frames: u32,
+    errors: u32,
+    render_last: u32,
+    render_max: u32,
+    transmit_last: u32,
+    transmit_max: u32,
+    average: u32,
+}
+
+/// The usage of a single console command, see [`CommandStats`].
+#[derive(Serialize)]
+struct CommandReport<'a> {
+    command: &'a str,
+    count: u32,
+    /// Seconds since boot.
+    last_run: Option<u64>,
+}
+
+/// A sensor and the error of its last sample, if any, see [`Sample`].
+#[derive(Serialize)]
+struct SensorReport<'a> {
+    sensor: &'a str,
+    address: u8,
+    error: Option<&'a str>,
+}
+
+/// A sensor reading, see [`Reading`].
+#[derive(Serialize)]
+struct ReadingReport<'a> {
+    sensor: &'a str,
+    /// Hundredths of a degree Celsius.
+    temperature: i32,
+    /// Hundredths of a percent.
+    humidity: u32,
+}
+
+/// The supply state, see [`SupplyStatus`], the voltages are in millivolts.
+#[derive(Serialize)]
+struct SupplyReport {
+    voltage: u32,
+    threshold: u32,
+    low: bool,
+}
+
+/// A binary protocol message, hex encoded.
+#[derive(Serialize)]
+struct MessageReport<'a> {
+    message: &'a str,
+}
+
+/// A command which succeeded without any other output.
+#[derive(Serialize)]
+struct OkReport {
+    ok: bool,
+}
+
+/// An error reported by a command.
+#[derive(Serialize)]
+struct ErrorReport<'a> {
+    error: &'a str,
+}
+
+/// Prints boot diagnostics to [`Terminal`] as a JSON line.
+pub async fn print_bootinfo<T: Terminal>(term: &mut T, info: &BootInfo) -> Result<(), T::Error> {
+    let reset_reason = info.reset_reason.map(|reason| format!("{:?}", reason));
+    let wakeup_cause = format!("{:?}", info.wakeup_cause);
+
+    write_line(
+        term,
+        &BootReport {
+            reset_reason: reset_reason.as_deref(),
+            wakeup_cause: &wakeup_cause,
+            brownout: info.brownout(),
+            unclean_shutdown: info.unclean_shutdown,
+            unclean_streak: info.unclean_streak,
+            safe_mode: info.safe_mode,
+            heap_used: esp_alloc::HEAP.used(),
+            heap_free: esp_alloc::HEAP.free(),
+        },
+    )
+    .await
+}
+
+/// Prints the firmware build information to [`Terminal`] as a JSON line.
+pub async fn print_version<T: Terminal>(term: &mut T) -> Result<(), T::Error> {
+    write_line(
+        term,
+        &VersionReport {
+            name: BUILD_INFO.name,
+            version: BUILD_INFO.version,
+            commit: BUILD_INFO.commit,
+            branch: BUILD_INFO.branch,
+            build_time: BUILD_INFO.build_time,
+            rustc: BUILD_INFO.rustc,
+            device: BUILD_INFO.device,
+            features: Features,
+        },
+    )
+    .await
+}
+
+/// Prints the last frame transmitted to the LED to [`Terminal`] as a JSON line.
+pub async fn print_led_frame<T: Terminal>(
+    term: &mut T,
+    frame: Option<Frame>,
+) -> Result<(), T::Error> {
+    let Some(frame) = frame else {
+        return print_error(term, "nothing transmitted yet").await;
+    };
+
+    let RGB { r, g, b } = frame.color;
+    let color = format!("#{r:02x}{g:02x}{b:02x}");
+    write_line(
+        term,
+        &FrameReport {
+            color: &color,
+            bits: frame.bits,
+        },
+    )
+    .await
+}
+
+/// Prints the LED timing statistics to [`Terminal`] as a JSON line.
+pub async fn print_led_stats<T: Terminal>(term: &mut T, stats: &Stats) -> Result<(), T::Error> {
+    write_line(
+        term,
+        &StatsReport {
+            frames: stats.frames,
+            errors: stats.errors,
+            render_last: stats.render_last,
+            render_max: stats.render_max,
+            transmit_last: stats.transmit_last,
+            transmit_max: stats.transmit_max,
+            average: stats.average(),
+        },
+    )
+    .await
+}
+
+/// Prints the usage statistics of the console commands to [`Terminal`], one JSON line per
+/// command.
+pub async fn print_command_stats<T: Terminal>(
+    term: &mut T,
+    stats: &CommandStats,
+) -> Result<(), T::Error> {
+    for (command, usage) in stats.iter() {
+        let report = CommandReport {
+            command,
+            count: usage.count,
+            last_run: usage.last_run.map(|last_run| last_run.as_secs()),
+        };
+        write_line(term, &report).await?;
+    }
+    Ok(())
+}
+
+/// Prints the sensors and the state of their last sample to [`Terminal`], one JSON line per
+/// sensor.
+pub async fn print_sensors<T: Terminal>(
+    term: &mut T,
+    sample: Option<Sample>,
+) -> Result<(), T::Error> {
+    let Some(sample) = sample else {
+        return print_error(term, "no sensor sampled yet").await;
+    };
+
+    let error = sample.reading.err().map(|e| format!("{e}"));
+    let report = SensorReport {
+        sensor: sample.sensor,
+        address: sample.address,
+        error: error.as_deref(),
+    };
+    write_line(term, &report).await
+}
+
+/// Prints the last sensor reading to [`Terminal`] as a JSON line.
+pub async fn print_reading<T: Terminal>(
+    term: &mut T,
+    sample: Option<Sample>,
+) -> Result<(), T::Error> {
+    let Some(sample) = sample else {
+        return print_error(term, "no sensor sampled yet").await;
+    };
+
+    match sample.reading {
+        Ok(Reading {
+            temperature,
+            humidity,
+        }) => {
+            let report = ReadingReport {
+                sensor: sample.sensor,
+                temperature,
+                humidity,
+            };
+            write_line(term, &report).await
+        }
+        Err(e) => print_error(term, &format!("{} reading failed: {e}", sample.sensor)).await,
+    }
+}
+
+/// Prints the supply state to [`Terminal`] as a JSON line.
+pub async fn print_supply<T: Terminal>(
+    term: &mut T,
+    status: Option<SupplyStatus>,
+) -> Result<(), T::Error> {
+    let Some(status) = status else {
+        return print_error(term, "no supply measurement yet").await;
+    };
+
+    let report = SupplyReport {
+        voltage: status.voltage,
+        threshold: status.threshold,
+        low: status.low,
+    };
+    write_line(term, &report).await
+}
+
+/// Prints a binary protocol message as hex to [`Terminal`] as a JSON line.
+pub async fn print_message<T: Terminal>(
+    term: &mut T,
+    message: Option<&[u8]>,
+) -> Result<(), T::Error> {
+    let Some(message) = message else {
+        return print_error(term, "no message").await;
+    };
+
+    let hex: String = message.iter().map(|byte| format!("{byte:02x}")).collect();
+    write_line(term, &MessageReport { message: &hex }).await
+}
+
+/// Prints the success of a command without any other output to [`Terminal`] as a JSON line.
+pub async fn print_ok<T: Terminal>(term: &mut T) -> Result<(), T::Error> {
+    write_line(term, &OkReport { ok: true }).await
+}
+
+/// Prints an error message to [`Terminal`] as a JSON line.
+pub async fn print_error<T: Terminal>(term: &mut T, error: &str) -> Result<(), T::Error> {
+    write_line(term, &ErrorReport { error }).await
+}
+
+/// Serialize `value` and write it to `term`, followed by a line break.
+async fn write_line<T: Terminal, V: Serialize>(term: &mut T, value: &V) -> Result<(), T::Error> {
+    let mut buf = [0; LINE_BUFFER_SIZE];
+    match serde_json_core::to_slice(value, &mut buf) {
+        Ok(len) => term.write(&buf[..len]).await?,
+        Err(e) => {
+            defmt::warn!("JSON serialization failed: {}", defmt::Debug2Format(&e));
+            term.write(br#"{"error":"serialization failed"}"#).await?;
+        }
+    }
+    term.write(b"\r\n").await
+}
@@ -0,0 +1,123 @@
+//! Idle power management using light sleep.
+
+use esp_hal::{
+    rtc_cntl::{
+        Rtc,
+        sleep::{TimerWakeupSource, Uart0WakeupSource},
+    },
+    time::{Duration, Instant},
+};
+
+/// Number of RX edges required to wake the chip up from light sleep.
+///
+/// The characters received before the chip wakes up are lost, a single keystroke is enough to
+/// wake the console.
+const UART_WAKEUP_THRESHOLD: u16 = 1;
+
+/// Default console inactivity period after which [`PowerMode::Auto`] enters light sleep.
+const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Console inactivity period after which [`PowerMode::On`] enters light sleep.
+const ON_IDLE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Maximum duration of a single light sleep period.
+///
+/// Timers are not serviced while the chip sleeps, so it is woken up periodically to let the
+/// pending tasks make progress.
+const MAX_SLEEP: core::time::Duration = core::time::Duration::from_secs(1);
+
+/// The power management policy.
+#[derive(defmt::Format, Clone, Copy, PartialEq, Eq)]
+pub enum PowerMode {
+    /// Enter light sleep after the console has been idle for the idle timeout.
+    Auto,
+    /// Enter light sleep as soon as the console has been idle for a second.
+    On,
+    /// Never enter light sleep.
+    Off,
+}
+
+impl PowerMode {
+    /// Parse a power mode from its name (`auto`, `on` or `off`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "auto" => Some(Self::Auto),
+            "on" => Some(Self::On),
+            "off" => Some(Self::Off),
+            _ => None,
+        }
+    }
+}
+
+/// Light sleep manager driven by the console activity.
+///
+/// Call [`PowerManager::activity`] on every byte received on the console and
+/// [`PowerManager::idle`] whenever the firmware has nothing to do.
+pub struct PowerManager<'d> {
+    rtc: Rtc<'d>,
+    mode: PowerMode,
+    idle_timeout: Duration,
+    last_activity: Instant,
+}
+
+impl<'d> PowerManager<'d> {
+    /// Construct a new [`PowerManager`] in the [`PowerMode::Auto`] mode.
+    pub fn new(rtc: Rtc<'d>) -> Self {
+        Self {
+            rtc,
+            mode: PowerMode::Auto,
+            idle_timeout: DEFAULT_IDLE_TIMEOUT,
+            last_activity: Instant::now(),
+        }
+    }
+
+    /// Set the console inactivity period used by [`PowerMode::Auto`].
+    pub fn with_idle_timeout(mut self, idle_timeout: Duration) -> Self {
+        self.idle_timeout = idle_timeout;
+        self
+    }
+
+    /// The current power management policy.
+    pub fn mode(&self) -> PowerMode {
+        self.mode
+    }
+
+    /// Set the power management policy.
+    pub fn set_mode(&mut self, mode: PowerMode) {
+        defmt::debug!("Power mode set to {:?}", mode);
+        self.mode = mode;
+    }
+
+    /// Record console activity.
+    pub fn activity(&mut self) {
+        self.last_activity = Instant::now();
+    }
+
+    /// Enter light sleep if the policy allows it.
+    ///
+    /// The chip is woken up by UART0 RX activity or after a short period of time. The character
+    /// waking the chip up is lost, so an early wakeup counts as console activity. Returns `true`
+    /// if the chip has slept.
+    pub fn idle(&mut self) -> bool {
+        let timeout = match self.mode {
+            PowerMode::Auto => Some(self.idle_timeout),
+            PowerMode::On => Some(ON_IDLE_TIMEOUT),
+            PowerMode::Off => None,
+        };
+        let sleep = timeout.is_some_and(|timeout| self.last_activity.elapsed() >= timeout);
+
+        if sleep {
+            let uart = Uart0WakeupSource::new(UART_WAKEUP_THRESHOLD);
+            let timer = TimerWakeupSource::new(MAX_SLEEP);
+            // the system timer stops in light sleep, the RTC one keeps counting
+            let start = self.rtc.time_since_boot();
+            self.rtc.sleep_light(&[&uart, &timer]);
+            let slept = self.rtc.time_since_boot() - start;
+            if slept.as_micros() < MAX_SLEEP.as_micros() as u64 / 2 {
+                self.activity();
+            }
+        }
+
+        sleep
+    }
+}
@@ -0,0 +1,168 @@
+//! Drivers for the environmental sensors connected over I2C.
+//!
+//! The [`sampler`] task reads the sensor periodically and publishes the samples in [`READINGS`],
+//! the consumers (e.g. the `sensor` command) never talk to the bus directly.
+
+use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, watch::Watch};
+use embassy_time::{Duration, Ticker, Timer};
+use esp_hal::{
+    Async,
+    i2c::master::{Error as I2cError, I2c},
+};
+
+/// Time between two samples taken by the [`sampler`].
+const SAMPLE_PERIOD: Duration = Duration::from_secs(10);
+
+/// Maximum number of receivers of [`READINGS`].
+const MAX_RECEIVERS: usize = 2;
+
+/// The latest [`Sample`] taken by the [`sampler`], empty until the first sample is taken.
+pub static READINGS: Watch<CriticalSectionRawMutex, Sample, MAX_RECEIVERS> = Watch::new();
+
+/// Default I2C address of the SHT3x sensor (ADDR pin pulled low).
+pub const SHT3X_ADDRESS: u8 = 0x44;
+
+/// Single shot measurement, high repeatability, clock stretching disabled.
+const SHT3X_MEASURE: [u8; 2] = [0x24, 0x00];
+
+/// Worst case duration of a high repeatability measurement in milliseconds.
+const SHT3X_MEASURE_TIME: u64 = 16;
+
+/// An error which occured while reading a sensor.
+#[derive(defmt::Format, Clone, Copy, PartialEq, Eq)]
+pub enum SensorError {
+    /// The I2C transaction failed.
+    Bus(I2cError),
+    /// The data recieved from the sensor did not match its checksum.
+    Checksum,
+}
+
+impl core::fmt::Display for SensorError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Bus(e) => write!(f, "bus error: {e}"),
+            Self::Checksum => f.write_str("checksum mismatch"),
+        }
+    }
+}
+
+impl From<I2cError> for SensorError {
+    fn from(value: I2cError) -> Self {
+        Self::Bus(value)
+    }
+}
+
+/// A single environmental reading.
+///
+/// The values are stored as fixed-point numbers to avoid float math.
+#[derive(defmt::Format, Clone, Copy, PartialEq, Eq)]
+pub struct Reading {
+    /// Temperature in hundredths of a degree Celsius.
+    pub temperature: i32,
+    /// Relative humidity in hundredths of a percent.
+    pub humidity: u32,
+}
+
+/// A sample taken by the [`sampler`].
+#[derive(defmt::Format, Clone, Copy, PartialEq, Eq)]
+pub struct Sample {
+    /// Name of the sensor model.
+    pub sensor: &'static str,
+    /// I2C address of the sensor.
+    pub address: u8,
+    /// The reading, or the error which occured while taking it.
+    pub reading: Result<Reading, SensorError>,
+}
+
+/// A Sensirion SHT3x temperature and humidity sensor driver.
+pub struct Sht3x<'d> {
+    i2c: I2c<'d, Async>,
+    address: u8,
+}
+
+impl<'d> Sht3x<'d> {
+    /// Construct a new [`Sht3x`] driver using the default address.
+    pub fn new(i2c: I2c<'d, Async>) -> Self {
+        Self::with_address(i2c, SHT3X_ADDRESS)
+    }
+
+    /// Construct a new [`Sht3x`] driver for a sensor at `address`.
+    pub fn with_address(i2c: I2c<'d, Async>, address: u8) -> Self {
+        Self { i2c, address }
+    }
+
+    /// The I2C address of the sensor.
+    pub fn address(&self) -> u8 {
+        self.address
+    }
+
+    /// Release the I2C bus.
+    pub fn release(self) -> I2c<'d, Async> {
+        self.i2c
+    }
+
+    /// Perform a single shot measurement.
+    pub async fn read(&mut self) -> Result<Reading, SensorError> {
+        self.i2c.write_async(self.address, &SHT3X_MEASURE).await?;
+        Timer::after_millis(SHT3X_MEASURE_TIME).await;
+
+        let mut buf = [0; 6];
+        self.i2c.read_async(self.address, &mut buf).await?;
+
+        let raw_temperature = Self::checked_word(&buf[0..3])?;
+        let raw_humidity = Self::checked_word(&buf[3..6])?;
+
+        // T = -45 + 175 * raw / (2^16 - 1), RH = 100 * raw / (2^16 - 1)
+        Ok(Reading {
+            temperature: -4500 + ((17500 * raw_temperature as i64) / 65535) as i32,
+            humidity: ((10000 * raw_humidity as u64) / 65535) as u32,
+        })
+    }
+
+    /// Extract a big endian word followed by its CRC-8 from `chunk`.
+    fn checked_word(chunk: &[u8]) -> Result<u16, SensorError> {
+        if Self::crc8(&chunk[..2]) == chunk[2] {
+            Ok(u16::from_be_bytes([chunk[0], chunk[1]]))
+        } else {
+            Err(SensorError::Checksum)
+        }
+    }
+
+    /// CRC-8 with polynomial `0x31` and initial value `0xff` as specified in the datasheet.
+    fn crc8(data: &[u8]) -> u8 {
+        let mut crc: u8 = 0xff;
+        for byte in data {
+            crc ^= byte;
+            for _ in 0..8 {
+                crc = if crc & 0x80 != 0 {
+                    (crc << 1) ^ 0x31
+                } else {
+                    crc << 1
+                };
+            }
+        }
+        crc
+    }
+}
+
+/// Read `sensor` every [`SAMPLE_PERIOD`] and publish the samples in [`READINGS`].
+#[embassy_executor::task]
+pub async fn sampler(mut sensor: Sht3x<'static>) {
+    let sender = READINGS.sender();
+    let mut ticker = Ticker::every(SAMPLE_PERIOD);
+
+    loop {
+        let reading = sensor.read().await;
+        match reading {
+            Ok(reading) => defmt::debug!("SHT3x reading: {}", reading),
+            Err(e) => defmt::warn!("SHT3x reading failed: {}", e),
+        }
+        sender.send(Sample {
+            sensor: "sht3x",
+            address: sensor.address(),
+            reading,
+        });
+
+        ticker.next().await;
+    }
+}
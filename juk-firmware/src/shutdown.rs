@@ -0,0 +1,92 @@
+//! Coordinated shutdown before an intentional reset.
+//!
+//! Subsystems which need to clean up before a reset (e.g. flush pending writes or turn off
+//! outputs) hold a [`Listener`]. When [`reset`] is called, all listeners are notified and the
+//! reset is delayed until every listener has been dropped, but no longer than [`DEADLINE`].
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use embassy_sync::{
+    blocking_mutex::raw::CriticalSectionRawMutex,
+    signal::Signal,
+    watch::{Receiver, Watch},
+};
+use embassy_time::{Duration, with_timeout};
+
+use crate::bootinfo;
+
+/// Maximum number of [`Listener`]s which can exist at the same time.
+const MAX_LISTENERS: usize = 4;
+
+/// Maximum time the listeners are given to clean up before the reset.
+pub const DEADLINE: Duration = Duration::from_millis(500);
+
+/// The shutdown reason, set once [`reset`] is called.
+static REASON: Watch<CriticalSectionRawMutex, ShutdownReason, MAX_LISTENERS> = Watch::new();
+
+/// Number of existing [`Listener`]s.
+static LISTENERS: AtomicUsize = AtomicUsize::new(0);
+
+/// Signalled whenever a [`Listener`] is dropped.
+static LISTENER_DROPPED: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+
+/// The reason of an intentional reset.
+#[derive(defmt::Format, Clone, Copy, PartialEq, Eq)]
+pub enum ShutdownReason {
+    /// A reboot was requested with a command.
+    Reboot,
+    /// CTRL + D was pressed on the console.
+    EndOfTransmission,
+}
+
+/// A handle used by a subsystem to get notified about a pending reset.
+///
+/// Wait for the notification with [`Listener::wait`], clean up and drop the listener to let the
+/// reset proceed.
+pub struct Listener {
+    receiver: Receiver<'static, CriticalSectionRawMutex, ShutdownReason, MAX_LISTENERS>,
+}
+
+impl Listener {
+    /// Construct a new [`Listener`].
+    ///
+    /// Returns `None` if there are already [`MAX_LISTENERS`] listeners.
+    pub fn new() -> Option<Self> {
+        let receiver = REASON.receiver()?;
+        LISTENERS.fetch_add(1, Ordering::SeqCst);
+        Some(Self { receiver })
+    }
+
+    /// Wait until a reset is requested.
+    pub async fn wait(&mut self) -> ShutdownReason {
+        self.receiver.get().await
+    }
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        LISTENERS.fetch_sub(1, Ordering::SeqCst);
+        LISTENER_DROPPED.signal(());
+    }
+}
+
+/// Notify all [`Listener`]s and reset the chip once they are done, or after [`DEADLINE`].
+pub async fn reset(reason: ShutdownReason) -> ! {
+    defmt::info!("Shutting down: {:?}", reason);
+    REASON.sender().send(reason);
+
+    let listeners_done = async {
+        while LISTENERS.load(Ordering::SeqCst) > 0 {
+            LISTENER_DROPPED.wait().await;
+        }
+    };
+    if with_timeout(DEADLINE, listeners_done).await.is_err() {
+        defmt::warn!(
+            "{} shutdown listener(s) did not finish in time",
+            LISTENERS.load(Ordering::SeqCst)
+        );
+    }
+
+    bootinfo::mark_clean_shutdown();
+    esp_hal::system::software_reset();
+}
@@ -1,33 +1,427 @@
-use juk_com::Terminal;
+use alloc::{format, string::String, vec::Vec};
+
 use const_format::formatc;
+use juk_com::{Color, Style, Terminal};
+use juk_led::{Frame, RGB, Stats};
+
+use crate::{
+    bootinfo::BootInfo,
+    metrics::CommandStats,
+    sensors::{Reading, Sample},
+    supply::SupplyStatus,
+};
 
 shadow_rs::shadow!(build);
 
-const INFO: &str = "\x1b[1;32m*\x1b[0m";
-const WARN: &str = "\x1b[1;33m*\x1b[0m";
+/// Bullet prefix for informational messages.
+pub const INFO: &str = "\x1b[1;32m*\x1b[0m";
+/// Bullet prefix for warning messages.
+pub const WARN: &str = "\x1b[1;33m*\x1b[0m";
+
+/// Style of the bullet of the informational messages printed with [`print_line`].
+const INFO_STYLE: Style = Style::new().with_bold().with_fg(Color::Green);
+/// Style of the bullet of the warning messages printed with [`print_line`].
+const WARN_STYLE: Style = Style::new().with_bold().with_fg(Color::Yellow);
 
 const LICENSE_1: &str = "juk-firmware Copyright (C) 2026 lysolaka";
 const LICENSE_2: &str = "License GNU GPL-3.0 <https://gnu.org/licenses/gpl.html>";
 const LICENSE_3: &str = "This is free software: you are free to change and redistribute it.";
 const LICENSE_4: &str = "There is ABSOLUTELY NO WARRANTY, to the extent permitted by law.";
 
-const LICENSE_NOTE: &str = formatc!("{0} {LICENSE_1}\r\n{0} {LICENSE_2}\r\n{0} {LICENSE_3}\r\n{1} {LICENSE_4}\r\n", INFO, WARN);
+/// Information about the running firmware build.
+pub const BUILD_INFO: BuildInfo = BuildInfo {
+    name: build::PROJECT_NAME,
+    version: build::PKG_VERSION,
+    commit: build::COMMIT_HASH,
+    branch: build::BRANCH,
+    build_time: build::BUILD_TIME,
+    rustc: build::RUST_VERSION,
+    device: option_env!("JUK_DEVICE_NAME"),
+    features: env!("JUK_FEATURES"),
+};
+
+const BROWNOUT_NOTE: &str = formatc!("{WARN} Reset caused by a brownout\r\n");
+const UNCLEAN_NOTE: &str = formatc!("{WARN} Previous session did not end cleanly (panic or watchdog)\r\n");
+
+/// Build information of the firmware, see [`BUILD_INFO`].
+#[derive(Clone, Copy)]
+pub struct BuildInfo {
+    /// Name of the firmware package.
+    pub name: &'static str,
+    /// Version of the firmware package.
+    pub version: &'static str,
+    /// Hash of the commit the firmware was built from.
+    pub commit: &'static str,
+    /// Branch the firmware was built from.
+    pub branch: &'static str,
+    /// Time of the build.
+    pub build_time: &'static str,
+    /// Version of the compiler used for the build.
+    pub rustc: &'static str,
+    /// Name of the device, set with the `JUK_DEVICE_NAME` environment variable at build time.
+    ///
+    /// The startup banner is only printed when the name is set.
+    pub device: Option<&'static str>,
+    features: &'static str,
+}
+
+impl BuildInfo {
+    /// Iterate over the cargo features enabled in the build.
+    pub fn features(&self) -> impl Iterator<Item = &'static str> {
+        self.features.split(',').filter(|feature| !feature.is_empty())
+    }
+}
+
+/// The user-facing messages of the console in a single language, see [`MESSAGES`].
+///
+/// The messages do not include the bullet prefixes and line breaks.
+pub struct Messages {
+    pub welcome: &'static str,
+    pub help_hint: &'static str,
+    pub text_mode: &'static str,
+    pub binary_mode: &'static str,
+    pub binary_mode_hint: &'static str,
+    pub busy: &'static str,
+    pub input_flood: &'static str,
+    pub rebooting: &'static str,
+    pub reboot_confirm: &'static str,
+    pub timed_out: &'static str,
+    pub invalid_color: &'static str,
+    /// Followed by the name of the command.
+    pub unknown_command: &'static str,
+    /// Followed by the new baud rate.
+    pub baud_switching: &'static str,
+    /// Followed by the rejected baud rate.
+    pub baud_unsupported: &'static str,
+    /// Followed by the new baud rate.
+    pub baud_set: &'static str,
+    pub memory_low: &'static str,
+    pub memory_critical: &'static str,
+    pub safe_mode: &'static str,
+    pub safe_mode_led: &'static str,
+    pub safe_mode_buzzer: &'static str,
+    pub safe_mode_power: &'static str,
+    /// Shown next to the title of a list to pick from.
+    pub pick_hint: &'static str,
+    pub pick_gamma: &'static str,
+    pub pick_order: &'static str,
+    pub pick_correction: &'static str,
+    pub pick_power: &'static str,
+}
+
+impl Messages {
+    /// The English messages.
+    pub const EN: Messages = Messages {
+        welcome: "Welcome to JUK2",
+        help_hint: "Type `?` anytime for help",
+        text_mode: "Switching to text mode",
+        binary_mode: "Switching to binary mode",
+        binary_mode_hint: "Press \x1b[1;37mCTRL + Space\x1b[0m once or twice to leave",
+        busy: "Busy",
+        input_flood: "Input flood, ignoring input",
+        rebooting: "Rebooting",
+        reboot_confirm: "Reboot the device?",
+        timed_out: "Command timed out",
+        invalid_color: "Invalid color",
+        unknown_command: "Unknown command:",
+        baud_switching: "Switching baud rate to",
+        baud_unsupported: "Unsupported baud rate:",
+        baud_set: "Baud rate set to",
+        memory_low: "Memory is running low, the history was trimmed",
+        memory_critical: "Memory is critically low, the history was cleared",
+        safe_mode: "Safe mode: the previous sessions crashed, reboot to leave",
+        safe_mode_led: "The LED is disabled in the safe mode",
+        safe_mode_buzzer: "The buzzer is disabled in the safe mode",
+        safe_mode_power: "The power management is disabled in the safe mode",
+        pick_hint: "arrows select, ENTER picks, CTRL + X dismisses",
+        pick_gamma: "Gamma correction",
+        pick_order: "Color byte order",
+        pick_correction: "White point correction",
+        pick_power: "Power management",
+    };
 
-const VERSION_1: &str = formatc!("{}: {} [{}]", build::PROJECT_NAME, build::PKG_VERSION, build::RUST_VERSION);
-const VERSION_2: &str = formatc!("Date: {}", build::BUILD_TIME);
-const VERSION_3: &str = formatc!("Built from {}, on branch: {}", build::COMMIT_HASH, build::BRANCH);
+    /// The Polish messages.
+    pub const PL: Messages = Messages {
+        welcome: "Witaj w JUK2",
+        help_hint: "Wpisz `?` w dowolnej chwili, aby uzyskać pomoc",
+        text_mode: "Przełączanie w tryb tekstowy",
+        binary_mode: "Przełączanie w tryb binarny",
+        binary_mode_hint: "Naciśnij \x1b[1;37mCTRL + Spacja\x1b[0m raz lub dwa razy, aby wyjść",
+        busy: "Zajęty",
+        input_flood: "Zalew danych wejściowych, wejście zignorowane",
+        rebooting: "Ponowne uruchamianie",
+        reboot_confirm: "Uruchomić urządzenie ponownie?",
+        timed_out: "Przekroczono czas wykonania polecenia",
+        invalid_color: "Nieprawidłowy kolor",
+        unknown_command: "Nieznane polecenie:",
+        baud_switching: "Zmiana prędkości transmisji na",
+        baud_unsupported: "Nieobsługiwana prędkość transmisji:",
+        baud_set: "Ustawiono prędkość transmisji na",
+        memory_low: "Kończy się pamięć, historia została skrócona",
+        memory_critical: "Krytycznie mało pamięci, historia została wyczyszczona",
+        safe_mode: "Tryb awaryjny: poprzednie sesje uległy awarii, uruchom ponownie, aby wyjść",
+        safe_mode_led: "Dioda LED jest wyłączona w trybie awaryjnym",
+        safe_mode_buzzer: "Brzęczyk jest wyłączony w trybie awaryjnym",
+        safe_mode_power: "Zarządzanie energią jest wyłączone w trybie awaryjnym",
+        pick_hint: "strzałki wybierają, ENTER zatwierdza, CTRL + X anuluje",
+        pick_gamma: "Korekcja gamma",
+        pick_order: "Kolejność bajtów koloru",
+        pick_correction: "Korekcja punktu bieli",
+        pick_power: "Zarządzanie energią",
+    };
+}
 
-const VERSION_NOTE: &str = formatc!("{0} {VERSION_1}\r\n{0} {VERSION_2}\r\n{0} {VERSION_3}\r\n", INFO);
+/// The messages in the language selected at compile time with the `lang-*` features, English
+/// by default.
+#[cfg(not(feature = "lang-pl"))]
+pub const MESSAGES: Messages = Messages::EN;
+/// The messages in the language selected at compile time with the `lang-*` features, English
+/// by default.
+#[cfg(feature = "lang-pl")]
+pub const MESSAGES: Messages = Messages::PL;
 
-/// Welcome message to print when starting REPL.
-pub const WELCOME_MOTD: &str = formatc!("{0} Welcome to JUK2\r\n{0} Type `?` anytime for help\r\n", INFO);
+/// Notice printed by the [`juk_com::Interface`] when switching to text mode.
+pub const TEXT_MODE_NOTICE: &str = formatc!("\r\n{INFO} {}\r\n", MESSAGES.text_mode);
 
-/// Prints license and version info to [`Terminal`].
+/// Notice printed by the [`juk_com::Interface`] when switching to binary mode.
+pub const BINARY_MODE_NOTICE: &str = formatc!("\r\n{0} {1}\r\n{0} {2}\r\n", INFO, MESSAGES.binary_mode, MESSAGES.binary_mode_hint);
+
+/// Prints a single message line to [`Terminal`], with a bullet in `bullet` style.
+async fn print_line<T: Terminal>(term: &mut T, bullet: Style, text: &str) -> Result<(), T::Error> {
+    term.write_styled(bullet, "*").await?;
+    term.write(format!(" {text}\r\n").as_bytes()).await
+}
+
+/// Prints `name` in a box to [`Terminal`].
+///
+/// The box is drawn with the box-drawing characters, or with ASCII if the terminal does not
+/// understand ANSI escape sequences, as such terminals often lack UTF-8 support too.
+async fn print_banner<T: Terminal>(term: &mut T, name: &str) -> Result<(), T::Error> {
+    let (corners, horizontal, vertical) = if term.ansi() {
+        (["┌", "┐", "└", "┘"], "─", "│")
+    } else {
+        (["+", "+", "+", "+"], "-", "|")
+    };
+    let [top_left, top_right, bottom_left, bottom_right] = corners;
+    let rule = horizontal.repeat(name.chars().count() + 2);
+
+    term.write(format!("{top_left}{rule}{top_right}\r\n{vertical} ").as_bytes())
+        .await?;
+    term.write_styled(Style::new().with_bold(), name).await?;
+    term.write(format!(" {vertical}\r\n{bottom_left}{rule}{bottom_right}\r\n").as_bytes())
+        .await
+}
+
+/// Prints license and version info to [`Terminal`], preceded by the banner with the device name
+/// if it is set in [`BUILD_INFO`].
 pub async fn print_verinfo<T: Terminal>(term: &mut T) -> Result<(), T::Error> {
     term.write(b"\r\n\r\n").await?;
-    term.write(LICENSE_NOTE.as_bytes()).await?;
+    if let Some(device) = BUILD_INFO.device {
+        print_banner(term, device).await?;
+        term.write(b"\r\n").await?;
+    }
+    for line in [LICENSE_1, LICENSE_2, LICENSE_3] {
+        print_line(term, INFO_STYLE, line).await?;
+    }
+    print_line(term, WARN_STYLE, LICENSE_4).await?;
     term.write(b"\r\n").await?;
-    term.write(VERSION_NOTE.as_bytes()).await?;
+
+    let info = BUILD_INFO;
+    let version = format!("{}: {} [{}]", info.name, info.version, info.rustc);
+    print_line(term, INFO_STYLE, &version).await?;
+    print_line(term, INFO_STYLE, &format!("Date: {}", info.build_time)).await?;
+    let origin = format!("Built from {}, on branch: {}", info.commit, info.branch);
+    print_line(term, INFO_STYLE, &origin).await?;
+    let features: Vec<&str> = info.features().collect();
+    if !features.is_empty() {
+        let features = format!("Features: {}", features.join(" "));
+        print_line(term, INFO_STYLE, &features).await?;
+    }
+    term.write(b"\r\n").await
+}
+
+/// Prints the welcome message to [`Terminal`], when starting REPL.
+pub async fn print_motd<T: Terminal>(term: &mut T) -> Result<(), T::Error> {
+    print_line(term, INFO_STYLE, MESSAGES.welcome).await?;
+    print_line(term, INFO_STYLE, MESSAGES.help_hint).await
+}
+
+/// Prints boot diagnostics to [`Terminal`].
+pub async fn print_bootinfo<T: Terminal>(term: &mut T, info: &BootInfo) -> Result<(), T::Error> {
+    let reset_reason = match info.reset_reason {
+        Some(reason) => format!("{:?}", reason),
+        None => "Unknown".into(),
+    };
+
+    term.write(format!("{INFO} Reset reason: {reset_reason}\r\n").as_bytes()).await?;
+    term.write(format!("{INFO} Wakeup cause: {:?}\r\n", info.wakeup_cause).as_bytes()).await?;
+    if info.brownout() {
+        term.write(BROWNOUT_NOTE.as_bytes()).await?;
+    }
+    if info.unclean_shutdown {
+        term.write(UNCLEAN_NOTE.as_bytes()).await?;
+    }
+    if info.safe_mode {
+        term.write(format!("{WARN} {}\r\n", MESSAGES.safe_mode).as_bytes()).await?;
+    }
+    term.write(
+        format!(
+            "{INFO} Heap: {} bytes used, {} bytes free\r\n",
+            esp_alloc::HEAP.used(),
+            esp_alloc::HEAP.free()
+        )
+        .as_bytes(),
+    )
+    .await?;
     term.write(b"\r\n").await?;
     Ok(())
 }
+
+/// Prints the last frame transmitted to the LED to [`Terminal`].
+pub async fn print_led_frame<T: Terminal>(
+    term: &mut T,
+    frame: Option<Frame>,
+) -> Result<(), T::Error> {
+    let Some(frame) = frame else {
+        return term.write(format!("{WARN} Nothing transmitted yet\r\n").as_bytes()).await;
+    };
+
+    let RGB { r, g, b } = frame.color;
+    let color = format!("{INFO} Color: #{r:02x}{g:02x}{b:02x}\r\n");
+    term.write(color.as_bytes()).await?;
+    term.write(
+        format!(
+            "{INFO} Bits: {:08b} {:08b} {:08b}\r\n",
+            frame.bits >> 16,
+            (frame.bits >> 8) & 0xff,
+            frame.bits & 0xff
+        )
+        .as_bytes(),
+    )
+    .await
+}
+
+/// Prints the LED timing statistics to [`Terminal`].
+pub async fn print_led_stats<T: Terminal>(term: &mut T, stats: &Stats) -> Result<(), T::Error> {
+    let text = format!(
+        "{INFO} Frames: {} ({} failed)\r\n\
+         {INFO} Render: {} us last, {} us max\r\n\
+         {INFO} Transmit: {} us last, {} us max\r\n\
+         {INFO} Average frame: {} us\r\n",
+        stats.frames,
+        stats.errors,
+        stats.render_last,
+        stats.render_max,
+        stats.transmit_last,
+        stats.transmit_max,
+        stats.average()
+    );
+    term.write(text.as_bytes()).await
+}
+
+/// Prints the usage statistics of the console commands to [`Terminal`].
+pub async fn print_command_stats<T: Terminal>(
+    term: &mut T,
+    stats: &CommandStats,
+) -> Result<(), T::Error> {
+    for (command, usage) in stats.iter() {
+        let text = match usage.last_run {
+            Some(last_run) => format!(
+                "{INFO} {command}: {} run(s), last at {} s\r\n",
+                usage.count,
+                last_run.as_secs()
+            ),
+            None => format!("{INFO} {command}: never run\r\n"),
+        };
+        term.write(text.as_bytes()).await?;
+    }
+    Ok(())
+}
+
+/// Prints the sensors and the state of their last sample to [`Terminal`].
+pub async fn print_sensors<T: Terminal>(
+    term: &mut T,
+    sample: Option<Sample>,
+) -> Result<(), T::Error> {
+    let Some(sample) = sample else {
+        return term.write(format!("{WARN} No sensor sampled yet\r\n").as_bytes()).await;
+    };
+
+    let sensor = format!("{} at 0x{:02x}", sample.sensor, sample.address);
+    let text = match sample.reading {
+        Ok(_) => format!("{INFO} {sensor}: ok\r\n"),
+        Err(e) => format!("{WARN} {sensor}: {e}\r\n"),
+    };
+    term.write(text.as_bytes()).await
+}
+
+/// Prints the last sensor reading to [`Terminal`].
+pub async fn print_reading<T: Terminal>(
+    term: &mut T,
+    sample: Option<Sample>,
+) -> Result<(), T::Error> {
+    let Some(sample) = sample else {
+        return term.write(format!("{WARN} No sensor sampled yet\r\n").as_bytes()).await;
+    };
+
+    let text = match sample.reading {
+        Ok(Reading {
+            temperature,
+            humidity,
+        }) => format!(
+            "{INFO} Temperature: {} C\r\n{INFO} Humidity: {}.{:02} %\r\n",
+            centi(temperature),
+            humidity / 100,
+            humidity % 100
+        ),
+        Err(e) => format!("{WARN} {} reading failed: {e}\r\n", sample.sensor),
+    };
+    term.write(text.as_bytes()).await
+}
+
+/// Prints the supply state to [`Terminal`].
+pub async fn print_supply<T: Terminal>(
+    term: &mut T,
+    status: Option<SupplyStatus>,
+) -> Result<(), T::Error> {
+    let Some(status) = status else {
+        return term.write(format!("{WARN} No supply measurement yet\r\n").as_bytes()).await;
+    };
+
+    let SupplyStatus {
+        voltage,
+        threshold,
+        low,
+    } = status;
+    let text = if low {
+        format!("{WARN} Supply: {voltage} mV, low (below {threshold} mV)\r\n")
+    } else {
+        format!("{INFO} Supply: {voltage} mV (low below {threshold} mV)\r\n")
+    };
+    term.write(text.as_bytes()).await
+}
+
+/// Format a fixed-point number in hundredths, e.g. `-125` as `-1.25`.
+fn centi(value: i32) -> String {
+    let sign = if value < 0 { "-" } else { "" };
+    let value = value.unsigned_abs();
+    format!("{sign}{}.{:02}", value / 100, value % 100)
+}
+
+/// Prints a binary protocol message as hex to [`Terminal`], `label` describes the message.
+pub async fn print_message<T: Terminal>(
+    term: &mut T,
+    label: &str,
+    message: Option<&[u8]>,
+) -> Result<(), T::Error> {
+    let Some(message) = message else {
+        return term.write(format!("{WARN} {label}: none\r\n").as_bytes()).await;
+    };
+
+    let mut text = format!("{INFO} {label}:");
+    for byte in message {
+        text.push_str(&format!(" {byte:02x}"));
+    }
+    text.push_str("\r\n");
+    term.write(text.as_bytes()).await
+}
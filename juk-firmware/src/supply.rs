@@ -0,0 +1,207 @@
+//! Supply (battery/VIN) voltage monitoring using the ADC.
+//!
+//! The [`monitor`] loop measures the supply voltage periodically, publishes the [`SupplyStatus`]
+//! in [`STATUS`] and fires the [`SupplyEvent`]s in [`EVENTS`].
+
+use embassy_futures::select::{Either, select};
+use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, signal::Signal, watch::Watch};
+use embassy_time::{Duration, Ticker};
+use esp_hal::{
+    Blocking,
+    analog::adc::{Adc, AdcCalCurve, AdcChannel, AdcConfig, AdcPin, Attenuation},
+    gpio::AnalogPin,
+    peripherals::ADC1,
+};
+
+/// Time between two supply voltage measurements of [`monitor`].
+const CHECK_PERIOD: Duration = Duration::from_secs(5);
+
+/// Maximum number of receivers of [`STATUS`].
+const MAX_RECEIVERS: usize = 2;
+
+/// The latest [`SupplyStatus`] measured by [`monitor`], empty until the first measurement.
+pub static STATUS: Watch<CriticalSectionRawMutex, SupplyStatus, MAX_RECEIVERS> = Watch::new();
+
+/// The latest [`SupplyEvent`] fired by [`monitor`].
+pub static EVENTS: Signal<CriticalSectionRawMutex, SupplyEvent> = Signal::new();
+
+/// A reference voltage in millivolts, [`monitor`] calibrates against it, see
+/// [`SupplyMonitor::calibrate`].
+pub static CALIBRATE: Signal<CriticalSectionRawMutex, u32> = Signal::new();
+
+/// Number of samples averaged for a single voltage reading.
+const SAMPLES: u32 = 8;
+
+/// Hysteresis applied to the low voltage threshold in millivolts.
+///
+/// Prevents firing events back and forth when the supply voltage hovers around the threshold.
+const HYSTERESIS: u32 = 100;
+
+/// Default low voltage threshold in millivolts (a nearly depleted single cell Li-ion battery).
+const DEFAULT_THRESHOLD: u32 = 3300;
+
+/// A resistor divider scaling the supply voltage down to the ADC input range.
+#[derive(defmt::Format, Clone, Copy)]
+pub struct Divider {
+    /// Resistance between the supply and the ADC pin in ohms.
+    pub top: u32,
+    /// Resistance between the ADC pin and ground in ohms.
+    pub bottom: u32,
+}
+
+/// An event fired by [`SupplyMonitor::poll`] when the supply state changes.
+#[derive(defmt::Format, Clone, Copy, PartialEq, Eq)]
+pub enum SupplyEvent {
+    /// The supply voltage dropped below the threshold. The payload is the voltage in millivolts.
+    Low(u32),
+    /// The supply voltage recovered above the threshold. The payload is the voltage in millivolts.
+    Restored(u32),
+}
+
+/// The supply state published by [`monitor`].
+#[derive(defmt::Format, Clone, Copy, PartialEq, Eq)]
+pub struct SupplyStatus {
+    /// The supply voltage in millivolts.
+    pub voltage: u32,
+    /// The low voltage threshold in millivolts.
+    pub threshold: u32,
+    /// Whether the supply voltage is considered low, see [`SupplyMonitor::is_low`].
+    pub low: bool,
+}
+
+/// A supply voltage monitor.
+///
+/// The ADC readings are corrected using the factory calibration (curve fitting) and can
+/// additionally be trimmed against a reference measurement with [`SupplyMonitor::calibrate`].
+pub struct SupplyMonitor<'d, PIN> {
+    adc: Adc<'d, ADC1<'d>, Blocking>,
+    pin: AdcPin<PIN, ADC1<'d>, AdcCalCurve<ADC1<'d>>>,
+    divider: Divider,
+    /// Correction gain in parts per million.
+    gain: u32,
+    threshold: u32,
+    low: bool,
+    /// The voltage measured by the last [`SupplyMonitor::poll`] in millivolts.
+    last_voltage: u32,
+}
+
+impl<'d, PIN> SupplyMonitor<'d, PIN>
+where
+    PIN: AdcChannel + AnalogPin,
+{
+    /// Construct a new [`SupplyMonitor`] measuring the voltage on `pin` behind `divider`.
+    pub fn new(adc: ADC1<'d>, pin: PIN, divider: Divider) -> Self {
+        let mut config = AdcConfig::new();
+        let pin = config.enable_pin_with_cal::<_, AdcCalCurve<ADC1<'d>>>(pin, Attenuation::_11dB);
+
+        Self {
+            adc: Adc::new(adc, config),
+            pin,
+            divider,
+            gain: 1_000_000,
+            threshold: DEFAULT_THRESHOLD,
+            low: false,
+            last_voltage: 0,
+        }
+    }
+
+    /// Set the low voltage threshold in millivolts.
+    pub fn with_threshold(mut self, threshold: u32) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// The low voltage threshold in millivolts.
+    pub fn threshold(&self) -> u32 {
+        self.threshold
+    }
+
+    /// Check whether the supply voltage is currently considered low.
+    pub fn is_low(&self) -> bool {
+        self.low
+    }
+
+    /// The voltage measured by the last [`SupplyMonitor::poll`] in millivolts, `0` before the
+    /// first poll.
+    pub fn last_voltage(&self) -> u32 {
+        self.last_voltage
+    }
+
+    /// Measure the supply voltage in millivolts.
+    pub fn voltage(&mut self) -> u32 {
+        (self.uncorrected_voltage() as u64 * self.gain as u64 / 1_000_000) as u32
+    }
+
+    /// Trim the measurement against a reference `actual` voltage in millivolts.
+    ///
+    /// Measure the supply voltage with a multimeter and pass the result to this function.
+    pub fn calibrate(&mut self, actual: u32) {
+        let measured = self.uncorrected_voltage();
+        if measured == 0 {
+            defmt::warn!("Supply voltage reads 0 mV, not calibrating");
+            return;
+        }
+
+        self.gain = (actual as u64 * 1_000_000 / measured as u64) as u32;
+        defmt::debug!("Supply monitor gain set to {} ppm", self.gain);
+    }
+
+    /// Measure the supply voltage and check it against the threshold.
+    ///
+    /// Returns an event if the supply state changed since the last call.
+    pub fn poll(&mut self) -> Option<SupplyEvent> {
+        let voltage = self.voltage();
+        self.last_voltage = voltage;
+
+        if !self.low && voltage < self.threshold {
+            self.low = true;
+            defmt::warn!("Supply voltage low: {} mV", voltage);
+            Some(SupplyEvent::Low(voltage))
+        } else if self.low && voltage >= self.threshold + HYSTERESIS {
+            self.low = false;
+            defmt::info!("Supply voltage restored: {} mV", voltage);
+            Some(SupplyEvent::Restored(voltage))
+        } else {
+            None
+        }
+    }
+
+    /// Averaged supply voltage in millivolts without the calibration gain applied.
+    fn uncorrected_voltage(&mut self) -> u32 {
+        let mut sum = 0;
+        for _ in 0..SAMPLES {
+            sum += self.adc.read_blocking(&mut self.pin) as u32;
+        }
+        let pin_voltage = sum / SAMPLES;
+
+        let ratio = (self.divider.top + self.divider.bottom) as u64;
+        (pin_voltage as u64 * ratio / self.divider.bottom as u64) as u32
+    }
+}
+
+/// Poll `monitor` every [`CHECK_PERIOD`], publishing the results in [`STATUS`] and [`EVENTS`].
+///
+/// The reference voltages signalled in [`CALIBRATE`] are applied in between the measurements.
+pub async fn monitor<PIN>(mut monitor: SupplyMonitor<'_, PIN>) -> !
+where
+    PIN: AdcChannel + AnalogPin,
+{
+    let sender = STATUS.sender();
+    let mut ticker = Ticker::every(CHECK_PERIOD);
+
+    loop {
+        if let Some(event) = monitor.poll() {
+            EVENTS.signal(event);
+        }
+        sender.send(SupplyStatus {
+            voltage: monitor.last_voltage(),
+            threshold: monitor.threshold(),
+            low: monitor.is_low(),
+        });
+
+        match select(ticker.next(), CALIBRATE.wait()).await {
+            Either::First(()) => (),
+            Either::Second(actual) => monitor.calibrate(actual),
+        }
+    }
+}
@@ -0,0 +1,181 @@
+//! Asynchronous animations of an [`LEDAdapter`], e.g. the status patterns of the firmware.
+//!
+//! [`fade_to`] and [`fade_to_dithered`] complete when the LED reaches the new color, while
+//! [`blink`], [`breathe`], [`rainbow`] and [`dither`] run until their future is dropped. This
+//! makes it easy to run a pattern while waiting for something else:
+//!
+//! ```
+//! use embassy_futures::select::select;
+//! use juk_led::{RGB, animation};
+//!
+//! let blue = RGB::new(0x00, 0x00, 0xff);
+//! let green = RGB::new(0x00, 0xff, 0x00);
+//!
+//! // breathe in blue until the connection is established
+//! select(animation::breathe(&mut led, blue), connect()).await;
+//! animation::fade_to(&mut led, green, Duration::from_millis(500)).await;
+//! ```
+//!
+//! The animations ignore the transition duration of the adapter, the LED keeps the last
+//! transmitted color when the future is dropped.
+
+use embassy_time::{Duration, Ticker};
+use esp_hal::Async;
+
+use crate::{Dither, HSV, LEDAdapter, RGB, RGB16, TRANSITION_STEP};
+
+/// The duration of a single breath of [`breathe`].
+const BREATHE_PERIOD: Duration = Duration::from_secs(4);
+
+/// Time between two frames of the dithered animations in milliseconds, the frames alternate
+/// between the 8-bit colors, so they have to be fast enough to not flicker.
+const DITHER_STEP: u64 = 2;
+
+/// Cross-fade from the current color of the LED to `color` over `duration`.
+pub async fn fade_to(led: &mut LEDAdapter<'_, Async>, color: impl Into<RGB>, duration: Duration) {
+    if let Err(e) = led.fade(&color.into(), duration).await {
+        warn!("LED color not set: {:?}", e);
+    }
+}
+
+/// Cross-fade from the current color of the LED to the 16-bit `color` over `duration`, dithering
+/// the frames, see [`RGB16`].
+///
+/// The fade ends at the 8-bit color nearest to `color`, continue with [`dither`] to keep showing
+/// `color` itself.
+pub async fn fade_to_dithered(
+    led: &mut LEDAdapter<'_, Async>,
+    color: impl Into<RGB16>,
+    duration: Duration,
+) {
+    let color = color.into();
+    let from = RGB16::from(led.color());
+    let steps = (duration.as_millis() / DITHER_STEP).max(1);
+    let mut dither = Dither::new();
+    let mut ticker = Ticker::every(Duration::from_millis(DITHER_STEP));
+    for step in 1..steps {
+        ticker.next().await;
+        let frame = from.blend(&color, step, steps);
+        led.transmit_dithered(&frame, &mut dither).await;
+    }
+    ticker.next().await;
+    led.transmit(&color.to_rgb()).await;
+}
+
+/// Show the 16-bit `color`, dithering the frames, see [`RGB16`].
+pub async fn dither(led: &mut LEDAdapter<'_, Async>, color: impl Into<RGB16>) -> ! {
+    let color = color.into();
+    let mut dither = Dither::new();
+    let mut ticker = Ticker::every(Duration::from_millis(DITHER_STEP));
+    loop {
+        led.transmit_dithered(&color, &mut dither).await;
+        ticker.next().await;
+    }
+}
+
+/// Blink the LED in `color`, it is lit for the first half of every `period` and black for the
+/// second half.
+pub async fn blink(led: &mut LEDAdapter<'_, Async>, color: impl Into<RGB>, period: Duration) -> ! {
+    let color = color.into();
+    let black = RGB::new(0x00, 0x00, 0x00);
+    let mut ticker = Ticker::every(period / 2);
+    loop {
+        led.transmit(&color).await;
+        ticker.next().await;
+        led.transmit(&black).await;
+        ticker.next().await;
+    }
+}
+
+/// Slowly brighten and dim the LED in `color`, one breath takes [`BREATHE_PERIOD`].
+pub async fn breathe(led: &mut LEDAdapter<'_, Async>, color: impl Into<RGB>) -> ! {
+    let color = color.into();
+    let black = RGB::new(0x00, 0x00, 0x00);
+    // half of the frames brighten the LED, the other half dims it
+    let steps = LEDAdapter::<Async>::transition_steps(BREATHE_PERIOD / 2);
+    let mut ticker = Ticker::every(Duration::from_millis(TRANSITION_STEP));
+    loop {
+        for step in (0..steps).chain((1..=steps).rev()) {
+            led.transmit(&black.blend(&color, step, steps)).await;
+            ticker.next().await;
+        }
+    }
+}
+
+/// Cycle the LED through the hues of the color wheel, see [`RainbowCycle`].
+pub async fn rainbow(led: &mut LEDAdapter<'_, Async>, speed: u16) -> ! {
+    let mut ticker = Ticker::every(Duration::from_millis(TRANSITION_STEP));
+    for color in RainbowCycle::new(speed) {
+        led.transmit(&color).await;
+        ticker.next().await;
+    }
+    unreachable!("`RainbowCycle` never ends")
+}
+
+/// An endless iterator over the colors of a hue rotation, advancing by `speed` degrees on every
+/// frame, using only integer math.
+///
+/// With a [`crate::StripAdapter`] the pixels can be spread over the color wheel, each frame is
+/// drawn with [`RainbowCycle::pixel`] before advancing the iterator:
+///
+/// ```
+/// let mut rainbow = RainbowCycle::new(2).with_spread(360 / N as u16);
+/// loop {
+///     for index in 0..N {
+///         strip.set_pixel(index, &rainbow.pixel(index));
+///     }
+///     strip.swap_and_show().await;
+///     rainbow.next();
+///     Timer::after_millis(20).await;
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RainbowCycle {
+    hue: u16,
+    speed: u16,
+    spread: u16,
+    value: u8,
+}
+
+impl RainbowCycle {
+    /// Constructor for the [`RainbowCycle`] struct, starting at red with the full brightness.
+    pub const fn new(speed: u16) -> Self {
+        Self {
+            hue: 0,
+            speed: speed % 360,
+            spread: 0,
+            value: 255,
+        }
+    }
+
+    /// Set the brightness of the colors, `0` is black.
+    pub const fn with_value(mut self, value: u8) -> Self {
+        self.value = value;
+        self
+    }
+
+    /// Set the hue difference in degrees between the neighbouring pixels, see
+    /// [`RainbowCycle::pixel`].
+    pub const fn with_spread(mut self, spread: u16) -> Self {
+        self.spread = spread % 360;
+        self
+    }
+
+    /// The color of the pixel at `index` in the current frame.
+    pub fn pixel(&self, index: usize) -> RGB {
+        let offset = ((index % 360) as u32 * self.spread as u32 % 360) as u16;
+        HSV::new((self.hue + offset) % 360, 255, self.value).to_rgb()
+    }
+}
+
+impl Iterator for RainbowCycle {
+    type Item = RGB;
+
+    /// The color of the current frame, advancing to the next frame.
+    fn next(&mut self) -> Option<RGB> {
+        let color = self.pixel(0);
+        self.hue = (self.hue + self.speed) % 360;
+        Some(color)
+    }
+}
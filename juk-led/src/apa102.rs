@@ -0,0 +1,227 @@
+//! The [`Apa102Adapter`] driving a chain of APA102 or SK9822 LEDs over SPI.
+
+use embedded_hal_async::spi::SpiBus;
+use esp_hal::{
+    Async,
+    Blocking,
+    DriverMode,
+    spi::{Error, master::Spi},
+};
+
+use crate::{ColorCorrection, Gamma, RGB};
+
+/// The frame starting a transmission.
+const START_FRAME: [u8; 4] = [0x00; 4];
+
+/// The top 3 bits of the first byte of every LED frame.
+const LED_FRAME_MARKER: u8 = 0xe0;
+
+/// The highest value of the 5-bit global brightness.
+pub const MAX_BRIGHTNESS: u8 = 0x1f;
+
+/// A driver for a chain of `N` APA102 or SK9822 RGB LEDs.
+///
+/// Unlike the WS2812B, these LEDs have separate data and clock lines driven by an SPI
+/// peripheral, so they can be connected to any pin. The API mirrors [`crate::StripAdapter`],
+/// without the double buffering: the colors are set in a single frame buffer and transmitted with
+/// [`Apa102Adapter::show`], while
+/// [`Apa102Adapter::set_color`] sets all the LEDs at once like [`crate::LEDAdapter::set_color`].
+/// The errors are handled by emiting a warning message, [`Apa102Adapter::show_checked`] returns
+/// them instead. With the `smart-leds` feature, it implements the same smart-leds write traits as
+/// the other adapters.
+///
+/// The SPI bus is configured by the caller, the LEDs accept mode 0 up to a few MHz:
+///
+/// ```
+/// use esp_hal::spi::{
+///     Mode,
+///     master::{Config, Spi},
+/// };
+/// use juk_led::{Apa102Adapter, RGB};
+///
+/// let config = Config::default()
+///     .with_frequency(Rate::from_mhz(4))
+///     .with_mode(Mode::_0);
+/// let spi = Spi::new(peripherals.SPI2, config)
+///     .unwrap()
+///     .with_sck(peripherals.GPIO12)
+///     .with_mosi(peripherals.GPIO11);
+///
+/// let mut leds = Apa102Adapter::<_, 8>::new(spi);
+/// leds.set_color(&RGB::new(0xff, 0x00, 0xff));
+/// ```
+pub struct Apa102Adapter<'d, Dm, const N: usize>
+where
+    Dm: DriverMode,
+{
+    spi: Spi<'d, Dm>,
+    pixels: [RGB; N],
+    brightness: u8,
+    gamma: Gamma,
+    correction: ColorCorrection,
+}
+
+impl<'d, Dm, const N: usize> Apa102Adapter<'d, Dm, N>
+where
+    Dm: DriverMode,
+{
+    /// Construct a new [`Apa102Adapter`] from a configured SPI bus. All the pixels are initially
+    /// black and the global brightness is [`MAX_BRIGHTNESS`].
+    pub fn new(spi: Spi<'d, Dm>) -> Self {
+        Self {
+            spi,
+            pixels: [RGB::new(0x00, 0x00, 0x00); N],
+            brightness: MAX_BRIGHTNESS,
+            gamma: Gamma::Linear,
+            correction: ColorCorrection::NONE,
+        }
+    }
+
+    /// Set the 5-bit global brightness of the LEDs, values above [`MAX_BRIGHTNESS`] are clamped.
+    pub fn with_brightness(mut self, brightness: u8) -> Self {
+        self.set_brightness(brightness);
+        self
+    }
+
+    /// Set the 5-bit global brightness of the LEDs, values above [`MAX_BRIGHTNESS`] are clamped.
+    ///
+    /// The brightness is applied by the next [`Apa102Adapter::show`].
+    pub fn set_brightness(&mut self, brightness: u8) {
+        self.brightness = brightness.min(MAX_BRIGHTNESS);
+    }
+
+    /// Set the gamma correction applied to the transmitted colors.
+    pub fn with_gamma(mut self, gamma: Gamma) -> Self {
+        self.gamma = gamma;
+        self
+    }
+
+    /// Set the gamma correction applied to the transmitted colors.
+    pub fn set_gamma(&mut self, gamma: Gamma) {
+        self.gamma = gamma;
+    }
+
+    /// Set the white point correction applied to the transmitted colors, after the gamma
+    /// correction.
+    pub fn with_correction(mut self, correction: ColorCorrection) -> Self {
+        self.correction = correction;
+        self
+    }
+
+    /// Set the white point correction applied to the transmitted colors, after the gamma
+    /// correction.
+    ///
+    /// The correction is applied by the next [`Apa102Adapter::show`].
+    pub fn set_correction(&mut self, correction: ColorCorrection) {
+        self.correction = correction;
+    }
+
+    /// The white point correction applied to the transmitted colors.
+    pub fn correction(&self) -> ColorCorrection {
+        self.correction
+    }
+
+    /// Set the color of the pixel at `index` in the frame buffer, the first pixel is the one
+    /// closest to the controller.
+    ///
+    /// An out of range `index` is ignored with a warning.
+    pub fn set_pixel(&mut self, index: usize, color: &RGB) {
+        match self.pixels.get_mut(index) {
+            Some(pixel) => *pixel = *color,
+            None => warn!("Pixel {} out of range of a strip of {} LEDs", index, N),
+        }
+    }
+
+    /// Set all the pixels in the frame buffer to `color`.
+    pub fn fill(&mut self, color: &RGB) {
+        self.pixels = [*color; N];
+    }
+
+    /// The color of the pixel at `index` in the frame buffer.
+    pub fn pixel(&self, index: usize) -> Option<RGB> {
+        self.pixels.get(index).copied()
+    }
+
+    /// The frame buffer.
+    pub fn pixels(&self) -> &[RGB; N] {
+        &self.pixels
+    }
+
+    /// The frame of a single LED.
+    fn led_frame(&self, color: &RGB) -> [u8; 4] {
+        let RGB { r, g, b } = self.correction.apply(&self.gamma.apply(color));
+        [LED_FRAME_MARKER | self.brightness, b, g, r]
+    }
+
+    /// Number of 4-byte zero chunks ending a transmission.
+    ///
+    /// The SK9822 needs a 32-bit reset frame and the data is delayed by half a clock per LED, so
+    /// `N / 2` more clock edges are needed to push it to the last LED.
+    const fn end_chunks() -> usize {
+        (START_FRAME.len() + N.div_ceil(16)).div_ceil(4)
+    }
+}
+
+impl<'d, const N: usize> Apa102Adapter<'d, Blocking, N> {
+    /// Set all the LEDs to `color`, `color` can be an [`RGB`] or an [`HSV`](crate::HSV) color.
+    pub fn set_color(&mut self, color: impl Into<RGB>) {
+        self.fill(&color.into());
+        self.show();
+    }
+
+    /// Transmit the frame buffer to the LEDs. In case an SPI error happens, a warning log message
+    /// is emitted.
+    pub fn show(&mut self) {
+        if let Err(e) = self.show_checked() {
+            warn!("LED strip frame not shown: {:?}", e);
+        }
+    }
+
+    /// Transmit the frame buffer like [`Apa102Adapter::show`], but return the SPI error instead of
+    /// logging it.
+    pub fn show_checked(&mut self) -> Result<(), Error> {
+        debug!("Showing a frame of {} LEDs", N);
+        self.spi.write(&START_FRAME)?;
+        for i in 0..N {
+            let frame = self.led_frame(&self.pixels[i]);
+            self.spi.write(&frame)?;
+        }
+        for _ in 0..Self::end_chunks() {
+            self.spi.write(&START_FRAME)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'d, const N: usize> Apa102Adapter<'d, Async, N> {
+    /// Set all the LEDs to `color`, `color` can be an [`RGB`] or an [`HSV`](crate::HSV) color.
+    pub async fn set_color(&mut self, color: impl Into<RGB>) {
+        self.fill(&color.into());
+        self.show().await;
+    }
+
+    /// Transmit the frame buffer to the LEDs. In case an SPI error happens, a warning log message
+    /// is emitted.
+    pub async fn show(&mut self) {
+        if let Err(e) = self.show_checked().await {
+            warn!("LED strip frame not shown: {:?}", e);
+        }
+    }
+
+    /// Transmit the frame buffer like [`Apa102Adapter::show`], but return the SPI error instead of
+    /// logging it.
+    ///
+    /// The inherent `Spi::write` is blocking, the asynchronous one comes from [`SpiBus`].
+    pub async fn show_checked(&mut self) -> Result<(), Error> {
+        debug!("Showing a frame of {} LEDs", N);
+        SpiBus::write(&mut self.spi, &START_FRAME).await?;
+        for i in 0..N {
+            let frame = self.led_frame(&self.pixels[i]);
+            SpiBus::write(&mut self.spi, &frame).await?;
+        }
+        for _ in 0..Self::end_chunks() {
+            SpiBus::write(&mut self.spi, &START_FRAME).await?;
+        }
+        Ok(())
+    }
+}
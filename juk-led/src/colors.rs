@@ -0,0 +1,46 @@
+//! Named [`RGB`] color constants.
+//!
+//! The same names are accepted from the user by [`from_name`] and [`crate::parse_color`].
+
+use crate::RGB;
+
+pub const BLACK: RGB = RGB::new(0x00, 0x00, 0x00);
+pub const WHITE: RGB = RGB::new(0xff, 0xff, 0xff);
+pub const RED: RGB = RGB::new(0xff, 0x00, 0x00);
+pub const GREEN: RGB = RGB::new(0x00, 0xff, 0x00);
+pub const BLUE: RGB = RGB::new(0x00, 0x00, 0xff);
+pub const YELLOW: RGB = RGB::new(0xff, 0xff, 0x00);
+pub const CYAN: RGB = RGB::new(0x00, 0xff, 0xff);
+pub const MAGENTA: RGB = RGB::new(0xff, 0x00, 0xff);
+pub const ORANGE: RGB = RGB::new(0xff, 0x80, 0x00);
+pub const AMBER: RGB = RGB::new(0xff, 0xbf, 0x00);
+pub const PURPLE: RGB = RGB::new(0x80, 0x00, 0xff);
+pub const PINK: RGB = RGB::new(0xff, 0x40, 0x80);
+/// A white with a yellow tint, closer to an incandescent bulb than [`WHITE`].
+pub const WARM_WHITE: RGB = RGB::new(0xff, 0xb0, 0x60);
+
+/// The colors by name, the names are lowercase.
+const NAMES: &[(&str, RGB)] = &[
+    ("black", BLACK),
+    ("off", BLACK),
+    ("white", WHITE),
+    ("red", RED),
+    ("green", GREEN),
+    ("blue", BLUE),
+    ("yellow", YELLOW),
+    ("cyan", CYAN),
+    ("magenta", MAGENTA),
+    ("orange", ORANGE),
+    ("amber", AMBER),
+    ("purple", PURPLE),
+    ("pink", PINK),
+    ("warm-white", WARM_WHITE),
+];
+
+/// Look up a color by its name, e.g. `red` or `off`. The lookup is case insensitive.
+pub fn from_name(name: &str) -> Option<RGB> {
+    NAMES
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|&(_, color)| color)
+}
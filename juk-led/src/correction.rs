@@ -0,0 +1,70 @@
+//! The [`ColorCorrection`] of the white point of the LEDs.
+
+use crate::{RGB, RGB16};
+
+/// Per-channel scale factors applied to the transmitted colors, so that the whites look white.
+///
+/// The LEDs of different batches (and packages) do not have matched channels, e.g. the typical
+/// 5050 SMD LED is too green and too blue at the full white. A factor of `255` leaves the channel
+/// unchanged, `128` halves it. The correction is applied after the gamma correction, so it scales
+/// the actual LED output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ColorCorrection {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl ColorCorrection {
+    /// No correction, the default.
+    pub const NONE: Self = Self::new(0xff, 0xff, 0xff);
+
+    /// The typical WS2812B in a 5050 SMD package, e.g. on the strips.
+    pub const TYPICAL_SMD5050: Self = Self::new(0xff, 0xb0, 0xf0);
+
+    /// The typical 8 mm through-hole pixel.
+    pub const TYPICAL_PIXEL_8MM: Self = Self::new(0xff, 0xe0, 0x8c);
+
+    /// Constructor for the [`ColorCorrection`] struct.
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+
+    /// Parse a color correction from its name (`none`, `smd5050` or `pixel8mm`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "none" => Some(Self::NONE),
+            "smd5050" => Some(Self::TYPICAL_SMD5050),
+            "pixel8mm" => Some(Self::TYPICAL_PIXEL_8MM),
+            _ => None,
+        }
+    }
+
+    /// Apply the correction to `color`.
+    pub fn apply(&self, color: &RGB) -> RGB {
+        let scale = |c: u8, factor: u8| (c as u16 * factor as u16 / 255) as u8;
+        RGB::new(
+            scale(color.r, self.r),
+            scale(color.g, self.g),
+            scale(color.b, self.b),
+        )
+    }
+
+    /// Apply the correction to the 16-bit `color`.
+    pub(crate) fn apply16(&self, color: &RGB16) -> RGB16 {
+        let scale = |c: u16, factor: u8| (c as u32 * factor as u32 / 255) as u16;
+        RGB16::new(
+            scale(color.r, self.r),
+            scale(color.g, self.g),
+            scale(color.b, self.b),
+        )
+    }
+}
+
+impl Default for ColorCorrection {
+    fn default() -> Self {
+        Self::NONE
+    }
+}
@@ -0,0 +1,81 @@
+//! The [`RGB16`] color representation and the temporal dithering of its frames.
+
+use crate::RGB;
+
+/// A 16-bit RGB color, for the animations needing steps finer than the 8-bit [`RGB`].
+///
+/// The LED only takes 8-bit colors, so the animations of [`crate::animation`] taking an [`RGB16`]
+/// dither it over time: the frames alternate between the nearest 8-bit colors, averaging to the
+/// 16-bit one. This makes slow fades near black smooth, where a single 8-bit step is plainly
+/// visible.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RGB16 {
+    pub r: u16,
+    pub g: u16,
+    pub b: u16,
+}
+
+impl RGB16 {
+    /// Constructor for the [`RGB16`] struct.
+    pub const fn new(r: u16, g: u16, b: u16) -> Self {
+        RGB16 { r, g, b }
+    }
+
+    /// The nearest 8-bit color.
+    pub fn to_rgb(&self) -> RGB {
+        let round = |c: u16| ((c as u32 + 0x80) >> 8).min(0xff) as u8;
+        RGB::new(round(self.r), round(self.g), round(self.b))
+    }
+
+    /// The color `step / steps` of the way from `self` to `other`, see [`RGB::blend`].
+    pub fn blend(&self, other: &RGB16, step: u64, steps: u64) -> RGB16 {
+        if step >= steps {
+            return *other;
+        }
+        let mix =
+            |a: u16, b: u16| (a as i64 + (b as i64 - a as i64) * step as i64 / steps as i64) as u16;
+        RGB16::new(
+            mix(self.r, other.r),
+            mix(self.g, other.g),
+            mix(self.b, other.b),
+        )
+    }
+}
+
+/// Widen the 8-bit color, `0xff` gives `0xffff`.
+impl From<RGB> for RGB16 {
+    fn from(color: RGB) -> Self {
+        let widen = |c: u8| c as u16 * 0x101;
+        RGB16::new(widen(color.r), widen(color.g), widen(color.b))
+    }
+}
+
+/// The temporal dithering of the frames of a 16-bit color.
+///
+/// The part of every channel lost by truncating it to 8 bits is carried over to the next frame,
+/// so the average of the frames is the 16-bit color.
+pub(crate) struct Dither {
+    error: [u32; 3],
+}
+
+impl Dither {
+    /// Constructor for the [`Dither`] struct.
+    pub(crate) const fn new() -> Self {
+        Self { error: [0; 3] }
+    }
+
+    /// The 8-bit color of the next frame showing `color`.
+    pub(crate) fn frame(&mut self, color: &RGB16) -> RGB {
+        let [r, g, b] = &mut self.error;
+        let dither = |c: u16, error: &mut u32| {
+            let sum = c as u32 + *error;
+            let out = (sum >> 8).min(0xff);
+            // the error saturates at the top, where the frames cannot get any brighter
+            *error = (sum - (out << 8)).min(0xff);
+            out as u8
+        };
+        RGB::new(dither(color.r, r), dither(color.g, g), dither(color.b, b))
+    }
+}
@@ -0,0 +1,94 @@
+//! Gamma correction of the colors sent to the LEDs.
+
+use crate::{RGB, RGB16};
+
+/// Gamma 2.2 lookup table, `round((i / 255) ^ 2.2 * 255)`.
+static GAMMA_2_2: [u8; 256] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 2, 2, 2, 2, 2, 2, 2,
+    3, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 6, 6, 6, 6, 7, 7, 7, 8, 8, 8, 9, 9, 9, 10, 10, 11, 11,
+    11, 12, 12, 13, 13, 13, 14, 14, 15, 15, 16, 16, 17, 17, 18, 18, 19, 19, 20, 20, 21, 22, 22, 23,
+    23, 24, 25, 25, 26, 26, 27, 28, 28, 29, 30, 30, 31, 32, 33, 33, 34, 35, 35, 36, 37, 38, 39, 39,
+    40, 41, 42, 43, 43, 44, 45, 46, 47, 48, 49, 49, 50, 51, 52, 53, 54, 55, 56, 57, 58, 59, 60, 61,
+    62, 63, 64, 65, 66, 67, 68, 69, 70, 71, 73, 74, 75, 76, 77, 78, 79, 81, 82, 83, 84, 85, 87, 88,
+    89, 90, 91, 93, 94, 95, 97, 98, 99, 100, 102, 103, 105, 106, 107, 109, 110, 111, 113, 114, 116,
+    117, 119, 120, 121, 123, 124, 126, 127, 129, 130, 132, 133, 135, 137, 138, 140, 141, 143, 145,
+    146, 148, 149, 151, 153, 154, 156, 158, 159, 161, 163, 165, 166, 168, 170, 172, 173, 175, 177,
+    179, 181, 182, 184, 186, 188, 190, 192, 194, 196, 197, 199, 201, 203, 205, 207, 209, 211, 213,
+    215, 217, 219, 221, 223, 225, 227, 229, 231, 234, 236, 238, 240, 242, 244, 246, 248, 251, 253,
+    255,
+];
+
+/// Gamma 2.8 lookup table, `round((i / 255) ^ 2.8 * 255)`.
+static GAMMA_2_8: [u8; 256] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1,
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 2, 2, 2, 2, 2, 2, 2, 2, 3, 3, 3, 3, 3, 3, 3, 4, 4, 4, 4, 4, 5, 5, 5,
+    5, 6, 6, 6, 6, 7, 7, 7, 7, 8, 8, 8, 9, 9, 9, 10, 10, 10, 11, 11, 11, 12, 12, 13, 13, 13, 14,
+    14, 15, 15, 16, 16, 17, 17, 18, 18, 19, 19, 20, 20, 21, 21, 22, 22, 23, 24, 24, 25, 25, 26, 27,
+    27, 28, 29, 29, 30, 31, 32, 32, 33, 34, 35, 35, 36, 37, 38, 39, 39, 40, 41, 42, 43, 44, 45, 46,
+    47, 48, 49, 50, 50, 51, 52, 54, 55, 56, 57, 58, 59, 60, 61, 62, 63, 64, 66, 67, 68, 69, 70, 72,
+    73, 74, 75, 77, 78, 79, 81, 82, 83, 85, 86, 87, 89, 90, 92, 93, 95, 96, 98, 99, 101, 102, 104,
+    105, 107, 109, 110, 112, 114, 115, 117, 119, 120, 122, 124, 126, 127, 129, 131, 133, 135, 137,
+    138, 140, 142, 144, 146, 148, 150, 152, 154, 156, 158, 160, 162, 164, 167, 169, 171, 173, 175,
+    177, 180, 182, 184, 186, 189, 191, 193, 196, 198, 200, 203, 205, 208, 210, 213, 215, 218, 220,
+    223, 225, 228, 231, 233, 236, 239, 241, 244, 247, 249, 252, 255,
+];
+
+/// The gamma correction applied by an adapter before a color is transmitted.
+///
+/// The WS2812B output is linear in the PWM duty cycle, while the perceived brightness is not, so
+/// the uncorrected low brightness colors look washed out.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Gamma {
+    /// No correction.
+    #[default]
+    Linear,
+    /// Gamma 2.2, the sRGB approximation.
+    Gamma22,
+    /// Gamma 2.8, a stronger correction common for LEDs.
+    Gamma28,
+}
+
+impl Gamma {
+    /// Parse a gamma correction from its name (`off`, `2.2` or `2.8`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "off" => Some(Self::Linear),
+            "2.2" => Some(Self::Gamma22),
+            "2.8" => Some(Self::Gamma28),
+            _ => None,
+        }
+    }
+
+    /// Apply the correction to `color`.
+    pub fn apply(&self, color: &RGB) -> RGB {
+        let table = match self {
+            Self::Linear => return *color,
+            Self::Gamma22 => &GAMMA_2_2,
+            Self::Gamma28 => &GAMMA_2_8,
+        };
+        RGB::new(
+            table[color.r as usize],
+            table[color.g as usize],
+            table[color.b as usize],
+        )
+    }
+
+    /// Apply the correction to the 16-bit `color`, interpolating between the entries of the
+    /// lookup table.
+    pub(crate) fn apply16(&self, color: &RGB16) -> RGB16 {
+        let table = match self {
+            Self::Linear => return *color,
+            Self::Gamma22 => &GAMMA_2_2,
+            Self::Gamma28 => &GAMMA_2_8,
+        };
+        let correct = |c: u16| {
+            let (high, low) = ((c >> 8) as usize, (c & 0xff) as i32);
+            let from = table[high] as i32;
+            let to = table[(high + 1).min(0xff)] as i32;
+            ((from << 8) + (to - from) * low) as u16
+        };
+        RGB16::new(correct(color.r), correct(color.g), correct(color.b))
+    }
+}
@@ -0,0 +1,74 @@
+//! The [`StripGroup`] updating several strips in sync.
+
+use embassy_futures::join::join_array;
+use esp_hal::Async;
+
+use crate::{RGB, StripAdapter};
+
+/// A group of `S` strips of `N` LEDs each, driven by different RMT channels and pins.
+///
+/// [`StripGroup::swap_and_show_all`] starts the transmissions to all the strips at once and
+/// waits for all of them, so the segments of an installation update in sync instead of one after
+/// another. The pixels can be addressed per strip with [`StripGroup::strip`] or across the whole
+/// group, as if the strips were chained in order:
+///
+/// ```
+/// let mut group = StripGroup::new([
+///     StripAdapter::<_, 8>::new(rmt.channel0, peripherals.GPIO38, &mut pulses_0),
+///     StripAdapter::<_, 8>::new(rmt.channel1, peripherals.GPIO39, &mut pulses_1),
+/// ]);
+/// group.fill(&RGB::new(0x00, 0x00, 0x20));
+/// group.set_pixel(8, &RGB::new(0xff, 0x00, 0x00)); // the first pixel of the second strip
+/// group.swap_and_show_all().await;
+/// ```
+pub struct StripGroup<'ch, const N: usize, const S: usize> {
+    strips: [StripAdapter<'ch, Async, N>; S],
+}
+
+impl<'ch, const N: usize, const S: usize> StripGroup<'ch, N, S> {
+    /// Construct a new [`StripGroup`] from its strips, in order.
+    ///
+    /// The strips must have at least one LED, the pixels of the group are addressed by dividing
+    /// their index by `N`.
+    pub fn new(strips: [StripAdapter<'ch, Async, N>; S]) -> Self {
+        const { assert!(N > 0, "The strips of a group must have at least one LED") };
+        Self { strips }
+    }
+
+    /// The strip at `index`, e.g. to set its gamma correction, `None` if out of range.
+    pub fn strip(&mut self, index: usize) -> Option<&mut StripAdapter<'ch, Async, N>> {
+        self.strips.get_mut(index)
+    }
+
+    /// Give the strips back.
+    pub fn into_inner(self) -> [StripAdapter<'ch, Async, N>; S] {
+        self.strips
+    }
+
+    /// Set the color of the pixel at `index` of the whole group in the back buffers.
+    ///
+    /// An out of range `index` is ignored with a warning.
+    pub fn set_pixel(&mut self, index: usize, color: &RGB) {
+        match self.strips.get_mut(index / N) {
+            Some(strip) => strip.set_pixel(index % N, color),
+            None => warn!("Pixel {} out of range of a group of {} LEDs", index, N * S),
+        }
+    }
+
+    /// Set the color of all the pixels in the back buffers.
+    pub fn fill(&mut self, color: &RGB) {
+        for strip in &mut self.strips {
+            strip.fill(color);
+        }
+    }
+
+    /// Commit the back buffers of all the strips and transmit them together.
+    pub async fn swap_and_show_all(&mut self) {
+        join_array(self.strips.each_mut().map(|strip| strip.swap_and_show())).await;
+    }
+
+    /// Transmit the front buffers of all the strips together.
+    pub async fn show_all(&mut self) {
+        join_array(self.strips.each_mut().map(|strip| strip.show())).await;
+    }
+}
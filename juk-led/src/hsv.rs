@@ -0,0 +1,63 @@
+//! The [`HSV`] color representation.
+
+use crate::RGB;
+
+/// An HSV color, convertible to [`RGB`].
+///
+/// Working in HSV makes animations like hue sweeps or fading the brightness of a color simple:
+/// only one of the components needs to change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HSV {
+    /// Hue in degrees, values of 360 and above wrap around.
+    pub h: u16,
+    /// Saturation, `0` is gray and `255` is the pure hue.
+    pub s: u8,
+    /// Value (brightness), `0` is black.
+    pub v: u8,
+}
+
+impl HSV {
+    /// Constructor for the [`HSV`] struct.
+    pub const fn new(h: u16, s: u8, v: u8) -> Self {
+        HSV { h, s, v }
+    }
+
+    /// Convert the color to [`RGB`] using integer math, usable in constant expressions.
+    pub const fn to_rgb(&self) -> RGB {
+        let h = (self.h % 360) as u32;
+        let s = self.s as u32;
+        let v = self.v as u32;
+
+        let sector = h / 60;
+        // position within the sector, 0-255
+        let f = (h % 60) * 255 / 60;
+
+        let p = (v * (255 - s) / 255) as u8;
+        let q = (v * (255 * 255 - s * f) / (255 * 255)) as u8;
+        let t = (v * (255 * 255 - s * (255 - f)) / (255 * 255)) as u8;
+        let v = v as u8;
+
+        match sector {
+            0 => RGB::new(v, t, p),
+            1 => RGB::new(q, v, p),
+            2 => RGB::new(p, v, t),
+            3 => RGB::new(p, q, v),
+            4 => RGB::new(t, p, v),
+            _ => RGB::new(v, p, q),
+        }
+    }
+}
+
+impl From<HSV> for RGB {
+    fn from(color: HSV) -> Self {
+        color.to_rgb()
+    }
+}
+
+impl From<&HSV> for RGB {
+    fn from(color: &HSV) -> Self {
+        color.to_rgb()
+    }
+}
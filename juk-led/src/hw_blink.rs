@@ -0,0 +1,170 @@
+//! The [`HwBlink`] of an [`LEDAdapter`], repeated by the RMT peripheral on its own.
+
+use embassy_time::{Duration, Instant};
+use esp_hal::{
+    Blocking,
+    delay::Delay,
+    gpio::Level,
+    rmt::{
+        CHANNEL_RAM_SIZE,
+        ContinuousTxTransaction,
+        Error,
+        LoopMode,
+        MAX_TX_LOOPCOUNT,
+        PulseCode,
+    },
+};
+
+use crate::{LEDAdapter, RGB};
+
+/// Number of RMT memory blocks of the ESP32-S3, the most a single channel can be given.
+const MAX_BLOCKS: usize = 8;
+
+/// Number of RMT clock ticks per microsecond, the adapter runs at 80MHz.
+const TICKS_PER_MICRO: u64 = 80;
+
+/// The longest low period of a single [`PulseCode`], in RMT clock ticks.
+const MAX_HOLD: u64 = 2 * PulseCode::MAX_LEN as u64;
+
+impl<'ch> LEDAdapter<'ch, Blocking> {
+    /// Blink the LED in `color` without any CPU involvement: the LED shows `color` for the first
+    /// duration of `pattern` and is off for the second one, `repeats` times or until the returned
+    /// [`HwBlink`] is stopped if `None`. The gamma and white point corrections are applied.
+    ///
+    /// Both frames and the low periods between them are loaded into the channel RAM at once and
+    /// looped by the RMT peripheral. A [`PulseCode`] holds the line low for at most 819 µs, so
+    /// every block of the RAM given to the adapter with [`LEDAdapter::new_with_memory_blocks`]
+    /// beyond the first one, which the frames take, adds about 39 ms to the pattern. The full RAM
+    /// of the ESP32-S3 fits a pattern of 274 ms, a longer one returns [`Error::Overflow`].
+    ///
+    /// The repeats are limited to [`MAX_TX_LOOPCOUNT`], larger counts return
+    /// [`Error::InvalidArgument`].
+    pub fn blink_hw(
+        &mut self,
+        color: impl Into<RGB>,
+        pattern: (Duration, Duration),
+        repeats: Option<u16>,
+    ) -> Result<HwBlink<'_, 'ch>, Error> {
+        let mut codes = [PulseCode::end_marker(); MAX_BLOCKS * CHANNEL_RAM_SIZE];
+        let capacity = self.blocks as usize * CHANNEL_RAM_SIZE;
+        // the latch has to pass after every frame, also when the pattern is shorter
+        let latch = Duration::from_micros(self.timing.reset as u64);
+        let (on, off) = (pattern.0.max(latch), pattern.1.max(latch));
+        let frames = [(color.into(), on), (RGB::new(0x00, 0x00, 0x00), off)];
+
+        let mut len = 0;
+        for (color, hold) in frames {
+            if len + 24 > capacity {
+                return Err(Error::Overflow);
+            }
+            let output = self.correction.apply(&self.gamma.apply(&color));
+            output.to_pulses(&mut codes[len..len + 24], &self.timing, self.order);
+            len += 24;
+            len += Self::hold(&mut codes[len..capacity], hold)?;
+        }
+        // the loop starts over at the end marker
+        if len == capacity {
+            return Err(Error::Overflow);
+        }
+        len += 1;
+        debug!("Blinking the LED with {} pulse codes", len);
+
+        let mode = match repeats {
+            Some(repeats) if repeats > MAX_TX_LOOPCOUNT => return Err(Error::InvalidArgument),
+            Some(repeats) => LoopMode::Finite(repeats),
+            None => LoopMode::Infinite,
+        };
+
+        let ch = expect!(
+            self.channel.take(),
+            "At this point `self.channel` should be `Some`"
+        );
+        if let Some(left) = self.timing.latch_left(self.last_end) {
+            Delay::new().delay_micros(left.as_micros() as u32);
+        }
+        // the LED shows the frames of the blink from now on
+        self.stale = true;
+        match ch.transmit_continuously(&codes[..len], mode) {
+            Ok(tx) => Ok(HwBlink {
+                led: self,
+                tx: Some(tx),
+            }),
+            Err(_) => {
+                unreachable!("The blink is always a valid input to `ch.transmit_continuously()`")
+            }
+        }
+    }
+
+    /// Fill `codes` with low pulse codes lasting `duration`, returns the number of codes used.
+    fn hold(codes: &mut [PulseCode], duration: Duration) -> Result<usize, Error> {
+        let mut ticks = duration.as_micros() * TICKS_PER_MICRO;
+        let mut len = 0;
+        while ticks > 0 {
+            let code = codes.get_mut(len).ok_or(Error::Overflow)?;
+            let chunk = ticks.min(MAX_HOLD);
+            // a zero length would end the sequence
+            let first = chunk.div_ceil(2);
+            let second = (chunk - first).max(1);
+            *code = PulseCode::new(Level::Low, first as u16, Level::Low, second as u16);
+            ticks -= chunk;
+            len += 1;
+        }
+        Ok(len)
+    }
+}
+
+/// A blink started with [`LEDAdapter::blink_hw`], the RMT channel is given back to the adapter
+/// when it is stopped.
+///
+/// Dropping the blink stops it right away, possibly in the middle of a frame, so the LED may show
+/// a wrong color until the next one is set.
+pub struct HwBlink<'a, 'ch> {
+    led: &'a mut LEDAdapter<'ch, Blocking>,
+    tx: Option<ContinuousTxTransaction<'ch>>,
+}
+
+impl HwBlink<'_, '_> {
+    /// Check whether all the repeats have been sent, always `false` for an endless blink.
+    pub fn is_done(&self) -> bool {
+        self.tx
+            .as_ref()
+            .is_some_and(|tx| tx.is_loopcount_interrupt_set())
+    }
+
+    /// Stop the blink at the end of the current repeat, which turns the LED off.
+    ///
+    /// Blocks until the repeat ends, at most for the duration of the pattern.
+    pub fn stop(mut self) -> Result<(), Error> {
+        self.finish(false)
+    }
+
+    /// Stop the transmission, `immediate`ly or at the end of the current repeat, and give the
+    /// channel back to the adapter.
+    fn finish(&mut self, immediate: bool) -> Result<(), Error> {
+        let tx = match self.tx.take() {
+            Some(tx) => tx,
+            None => return Ok(()),
+        };
+        let result = if immediate { tx.stop() } else { tx.stop_next() };
+        self.led.last_end = Some(Instant::now());
+        self.led.color = RGB::new(0x00, 0x00, 0x00);
+        match result {
+            Ok(ch) => {
+                self.led.channel = Some(ch);
+                Ok(())
+            }
+            Err((e, ch)) => {
+                self.led.channel = Some(ch);
+                Err(e)
+            }
+        }
+    }
+}
+
+impl Drop for HwBlink<'_, '_> {
+    fn drop(&mut self) {
+        if let Err(e) = self.finish(true) {
+            warn!("LED blink not stopped cleanly: {:?}", e);
+        }
+    }
+}
@@ -14,41 +14,74 @@
 //! let mut led = LEDAdapter::new(rmt.channel0, peripherals.GPIO38); // construct the adapter
 //! led.set_color(&RGB::new(0xff, 0x00, 0xff)); // display your favourite color
 //! ```
+//!
+//! Color changes can cross-fade instead of snapping to the new color:
+//!
+//! ```
+//! let mut led = LEDAdapter::new(rmt.channel0, peripherals.GPIO38)
+//!     .with_transition(Duration::from_millis(500));
+//! led.set_color(&RGB::new(0x00, 0xff, 0x00)); // fades from the previous color over 500 ms
+//! ```
+//!
+//! # Logging
+//!
+//! The crate logs through `defmt` by default. Disable the default features and enable the `log`
+//! feature to log through the `log` crate instead.
 
 #![no_std]
 
+// must come first, so that the logging macros are visible in the other modules
+mod fmt;
+
+pub mod animation;
+pub mod colors;
+
+mod apa102;
+mod correction;
+mod dither;
+mod gamma;
+mod group;
+mod hsv;
+mod hw_blink;
+mod order;
+mod parse;
+#[cfg(feature = "smart-leds")]
+mod smart_leds;
+mod status;
+mod strip;
+mod timing;
+
+pub use apa102::{Apa102Adapter, MAX_BRIGHTNESS};
+pub use correction::ColorCorrection;
+use dither::Dither;
+pub use dither::RGB16;
+use embassy_time::{Duration, Instant, Ticker, Timer};
 use esp_hal::{
     Async,
     Blocking,
     DriverMode,
+    delay::Delay,
     gpio::{Level, interconnect::PeripheralOutput},
-    rmt::{Channel, PulseCode, Tx, TxChannelConfig, TxChannelCreator},
+    rmt::{Channel, Error, PulseCode, Tx, TxChannelConfig, TxChannelCreator},
 };
+pub use gamma::Gamma;
+pub use group::StripGroup;
+pub use hsv::HSV;
+pub use hw_blink::HwBlink;
+pub use order::ColorOrder;
+pub use parse::{ColorParseError, parse_color};
+pub use status::{Pattern, Status, StatusLed};
+pub use strip::{StripAdapter, pulses_len};
+pub use timing::LedTiming;
+use timing::PulseTable;
 
-// bit timings from the WS2812B datasheet
-const T0H: u32 = 350;
-const T0L: u32 = 800;
-
-const T1H: u32 = 700;
-const T1L: u32 = 600;
-
-// bit pulse codes calculated for an 80MHz peripheral clock
-const PULSE_0: PulseCode = PulseCode::new(
-    Level::High,
-    ((T0H * 80) / 1000) as u16,
-    Level::Low,
-    ((T0L * 80) / 1000) as u16,
-);
-
-const PULSE_1: PulseCode = PulseCode::new(
-    Level::High,
-    ((T1H * 80) / 1000) as u16,
-    Level::Low,
-    ((T1L * 80) / 1000) as u16,
-);
+/// Time between two frames of a transition in milliseconds.
+const TRANSITION_STEP: u64 = 20;
 
 /// A dead simple RGB 8-bit color representation.
-#[derive(defmt::Format, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RGB {
     pub r: u8,
     pub g: u8,
@@ -61,45 +94,214 @@ impl RGB {
         RGB { r, g, b }
     }
 
-    /// Convert the [`RGB`] color to the required [`PulseCode`] sequence. The sequence will be
-    /// saved to `pulses`.
+    /// Linearly interpolate between `self` and `other`, `t` of `0` gives `self` and `255` gives
+    /// `other`.
+    ///
+    /// Only integer math is used, e.g. `t` of `128` gives the color half way between the two.
+    pub fn lerp(self, other: RGB, t: u8) -> RGB {
+        self.blend(&other, t as u64, 255)
+    }
+
+    /// The color `step / steps` of the way from `self` to `other`, useful for the frames of a fade
+    /// or the pixels of a gradient.
+    ///
+    /// `step` is clamped to `steps`, a `steps` of `0` gives `other`.
+    pub fn blend(&self, other: &RGB, step: u64, steps: u64) -> RGB {
+        if step >= steps {
+            return *other;
+        }
+        let mix =
+            |a: u8, b: u8| (a as i64 + (b as i64 - a as i64) * step as i64 / steps as i64) as u8;
+        RGB::new(
+            mix(self.r, other.r),
+            mix(self.g, other.g),
+            mix(self.b, other.b),
+        )
+    }
+
+    /// Unpack a RGB565 color, as used by the displays.
+    ///
+    /// The channels are scaled to the full 8-bit range, e.g. `0xffff` gives white.
+    pub const fn from_rgb565(packed: u16) -> Self {
+        let (r, g, b) = (packed >> 11, (packed >> 5) & 0x3f, packed & 0x1f);
+        RGB::new(
+            ((r << 3) | (r >> 2)) as u8,
+            ((g << 2) | (g >> 4)) as u8,
+            ((b << 3) | (b >> 2)) as u8,
+        )
+    }
+
+    /// Pack the color as RGB565, dropping the lowest bits of each channel.
+    pub const fn to_rgb565(&self) -> u16 {
+        ((self.r as u16 >> 3) << 11) | ((self.g as u16 >> 2) << 5) | (self.b as u16 >> 3)
+    }
+
+    /// Convert the [`RGB`] color to the required [`PulseCode`] sequence with the bit `timing`,
+    /// sending the bytes in `order`. The sequence will be saved to the first 24 entries of
+    /// `pulses`.
+    ///
+    /// The pulse codes of the [`LedTiming`] presets are copied from a [`PulseTable`] precomputed
+    /// at compile time, custom timings are converted bit by bit.
     ///
     /// Note that the color format of the WS2812B LED is GRB.
-    fn to_pulses(&self, pulses: &mut [PulseCode; 25]) {
-        for pos in 0..8 {
-            match self.g & (1 << pos) {
-                0 => pulses[pos] = PULSE_0,
-                _ => pulses[pos] = PULSE_1,
+    fn to_pulses(&self, pulses: &mut [PulseCode], timing: &LedTiming, order: ColorOrder) {
+        let bytes = order.bytes(self);
+        let pulses = bytes.iter().zip(pulses.chunks_exact_mut(8));
+        match PulseTable::preset(timing) {
+            Some(table) => {
+                for (byte, pulses) in pulses {
+                    pulses.copy_from_slice(table.row(*byte));
+                }
             }
-        }
-        for pos in 0..8 {
-            match self.r & (1 << pos) {
-                0 => pulses[8 + pos] = PULSE_0,
-                _ => pulses[8 + pos] = PULSE_1,
+            None => {
+                let (pulse_0, pulse_1) = (timing.pulse_0(), timing.pulse_1());
+                for (byte, pulses) in pulses {
+                    for (pos, pulse) in pulses.iter_mut().enumerate() {
+                        match byte & (1 << pos) {
+                            0 => *pulse = pulse_0,
+                            _ => *pulse = pulse_1,
+                        }
+                    }
+                }
             }
         }
-        for pos in 0..8 {
-            match self.b & (1 << pos) {
-                0 => pulses[16 + pos] = PULSE_0,
-                _ => pulses[16 + pos] = PULSE_1,
-            }
+    }
+}
+
+impl From<&RGB> for RGB {
+    fn from(color: &RGB) -> Self {
+        *color
+    }
+}
+
+/// Unpack a `0x00RRGGBB` color, the highest byte is ignored.
+impl From<u32> for RGB {
+    fn from(packed: u32) -> Self {
+        RGB::new((packed >> 16) as u8, (packed >> 8) as u8, packed as u8)
+    }
+}
+
+/// Pack the color as `0x00RRGGBB`.
+impl From<RGB> for u32 {
+    fn from(color: RGB) -> Self {
+        ((color.r as u32) << 16) | ((color.g as u32) << 8) | color.b as u32
+    }
+}
+
+/// A frame transmitted to the LED, see [`LEDAdapter::last_frame`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Frame {
+    /// The color the frame was generated from.
+    pub color: RGB,
+    /// The 24 data bits in transmission order, the first transmitted bit is bit 23.
+    pub bits: u32,
+}
+
+impl Frame {
+    /// Decode the data bits from the `pulses` transmitted with the bit `timing`.
+    fn capture(color: &RGB, pulses: &[PulseCode; 25], timing: &LedTiming) -> Self {
+        let pulse_1 = timing.pulse_1();
+        let bits = pulses[..24]
+            .iter()
+            .fold(0, |bits, pulse| (bits << 1) | (*pulse == pulse_1) as u32);
+        Self {
+            color: *color,
+            bits,
+        }
+    }
+
+    /// The mask of the bits which differ between `self` and `other`.
+    pub fn diff(&self, other: &Frame) -> u32 {
+        self.bits ^ other.bits
+    }
+}
+
+/// Timing statistics of the frames transmitted by [`LEDAdapter`], see [`LEDAdapter::stats`].
+///
+/// All the times are in microseconds. The render time is the time spent converting a color to the
+/// RMT pulse codes, the transmit time is the time spent waiting for the RMT transmission.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Stats {
+    /// Number of frames transmitted successfully.
+    pub frames: u32,
+    /// Number of failed transmissions.
+    pub errors: u32,
+    /// Render time of the last frame.
+    pub render_last: u32,
+    /// The longest render time.
+    pub render_max: u32,
+    /// Transmit time of the last frame.
+    pub transmit_last: u32,
+    /// The longest transmit time.
+    pub transmit_max: u32,
+    /// Sum of the render and transmit times of all the frames.
+    pub total: u64,
+}
+
+impl Stats {
+    /// Account for a frame transmitted successfully.
+    fn record(&mut self, render: Duration, transmit: Duration) {
+        let render = render.as_micros() as u32;
+        let transmit = transmit.as_micros() as u32;
+
+        self.frames = self.frames.wrapping_add(1);
+        self.render_last = render;
+        self.render_max = self.render_max.max(render);
+        self.transmit_last = transmit;
+        self.transmit_max = self.transmit_max.max(transmit);
+        self.total += render as u64 + transmit as u64;
+    }
+
+    /// The average time of a frame (render and transmit), `0` if no frame was transmitted.
+    pub fn average(&self) -> u32 {
+        match self.frames {
+            0 => 0,
+            frames => (self.total / frames as u64) as u32,
         }
     }
 }
 
 /// A WS2812B RGB LED driver.
 ///
+/// The reset latch of the chip is kept between the frames, so the colors can be set back to back.
+///
+/// Other single-wire chips (e.g. the WS2811 or the WS2815) are supported by setting their bit
+/// timing with [`LEDAdapter::with_timing`].
+///
 /// This driver can work in synchronous and asyncronous modes depending on which driver mode the
 /// RMT peripheral was set up with.
 ///
 /// Since this is an LED driver and not something critical all errors are handled for by
 /// emiting a warning message.
+///
+/// When a transition duration is set with [`LEDAdapter::with_transition`], the color changes
+/// cross-fade from the current color to the new one.
+///
+/// The synchronous adapter can also blink the LED with no CPU involvement, see
+/// [`LEDAdapter::blink_hw`].
 pub struct LEDAdapter<'ch, Dm>
 where
     Dm: DriverMode,
 {
     channel: Option<Channel<'ch, Dm, Tx>>,
+    /// Number of the RMT memory blocks of the channel, see [`LEDAdapter::blink_hw`].
+    blocks: u8,
     buffer: [PulseCode; 25],
+    color: RGB,
+    transition: Duration,
+    gamma: Gamma,
+    correction: ColorCorrection,
+    timing: LedTiming,
+    order: ColorOrder,
+    /// The LED may not show `color` rendered with the current settings, so it has to be
+    /// retransmitted even if the color does not change.
+    stale: bool,
+    /// The end of the last transmission, see [`LedTiming::reset`].
+    last_end: Option<Instant>,
+    last_frame: Option<Frame>,
+    stats: Stats,
 }
 
 impl<'ch, Dm> LEDAdapter<'ch, Dm>
@@ -125,61 +327,361 @@ where
         C: TxChannelCreator<'ch, Dm>,
         O: PeripheralOutput<'ch>,
     {
-        let channel = defmt::expect!(
-            channel.configure_tx(pin, Self::channel_config()),
+        Self::new_with_memory_blocks(channel, pin, 1)
+    }
+
+    /// Construct a new [`LEDAdapter`] like [`LEDAdapter::new`], allocating `blocks` blocks of the
+    /// RMT channel RAM ([`CHANNEL_RAM_SIZE`](esp_hal::rmt::CHANNEL_RAM_SIZE) pulse codes each) to
+    /// the channel.
+    ///
+    /// A single block fits a frame, the others make room for the pattern of
+    /// [`LEDAdapter::blink_hw`]. The channels following this one cannot use the borrowed blocks.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if it fails to configure the RMT channel, e.g. when the blocks are
+    /// used by another channel.
+    pub fn new_with_memory_blocks<C, O>(channel: C, pin: O, blocks: u8) -> Self
+    where
+        C: TxChannelCreator<'ch, Dm>,
+        O: PeripheralOutput<'ch>,
+    {
+        let channel = expect!(
+            channel.configure_tx(pin, Self::channel_config().with_memsize(blocks)),
             "Failed to configure the RMT channel"
         );
 
         Self {
             channel: Some(channel),
+            blocks,
             buffer: [PulseCode::end_marker(); 25],
+            color: RGB::new(0x00, 0x00, 0x00),
+            transition: Duration::from_ticks(0),
+            gamma: Gamma::Linear,
+            correction: ColorCorrection::NONE,
+            timing: LedTiming::WS2812B,
+            order: ColorOrder::Grb,
+            stale: true,
+            last_end: None,
+            last_frame: None,
+            stats: Stats::default(),
         }
     }
+
+    /// Set the duration of the cross-fade performed on every color change.
+    pub fn with_transition(mut self, transition: Duration) -> Self {
+        self.transition = transition;
+        self
+    }
+
+    /// Set the duration of the cross-fade performed on every color change.
+    ///
+    /// A zero duration disables the transitions.
+    pub fn set_transition(&mut self, transition: Duration) {
+        self.transition = transition;
+    }
+
+    /// The duration of the cross-fade performed on every color change.
+    pub fn transition(&self) -> Duration {
+        self.transition
+    }
+
+    /// Set the gamma correction applied to the transmitted colors.
+    pub fn with_gamma(mut self, gamma: Gamma) -> Self {
+        self.gamma = gamma;
+        self
+    }
+
+    /// Set the gamma correction applied to the transmitted colors.
+    ///
+    /// The correction is applied to the next transmitted color.
+    pub fn set_gamma(&mut self, gamma: Gamma) {
+        self.gamma = gamma;
+        self.stale = true;
+    }
+
+    /// The gamma correction applied to the transmitted colors.
+    pub fn gamma(&self) -> Gamma {
+        self.gamma
+    }
+
+    /// Set the white point correction of the LED, applied after the gamma correction.
+    pub fn with_correction(mut self, correction: ColorCorrection) -> Self {
+        self.correction = correction;
+        self
+    }
+
+    /// Set the white point correction of the LED, applied after the gamma correction.
+    ///
+    /// The correction is applied to the next transmitted color.
+    pub fn set_correction(&mut self, correction: ColorCorrection) {
+        self.correction = correction;
+        self.stale = true;
+    }
+
+    /// The white point correction of the LED.
+    pub fn correction(&self) -> ColorCorrection {
+        self.correction
+    }
+
+    /// Set the bit timing of the LED chip, see the [`LedTiming`] presets.
+    pub fn with_timing(mut self, timing: LedTiming) -> Self {
+        self.timing = timing;
+        self
+    }
+
+    /// Set the bit timing of the LED chip, see the [`LedTiming`] presets.
+    pub fn set_timing(&mut self, timing: LedTiming) {
+        self.timing = timing;
+        self.stale = true;
+    }
+
+    /// The bit timing of the LED chip.
+    pub fn timing(&self) -> LedTiming {
+        self.timing
+    }
+
+    /// Set the order of the color bytes expected by the LED chip.
+    pub fn with_color_order(mut self, order: ColorOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Set the order of the color bytes expected by the LED chip.
+    pub fn set_color_order(&mut self, order: ColorOrder) {
+        self.order = order;
+        self.stale = true;
+    }
+
+    /// The order of the color bytes expected by the LED chip.
+    pub fn color_order(&self) -> ColorOrder {
+        self.order
+    }
+
+    /// The color currently displayed by the LED.
+    ///
+    /// During a transition this is the last transmitted intermediate color.
+    pub fn color(&self) -> RGB {
+        self.color
+    }
+
+    /// The last frame transmitted successfully, `None` if nothing has been transmitted yet.
+    pub fn last_frame(&self) -> Option<Frame> {
+        self.last_frame
+    }
+
+    /// The timing statistics of the transmitted frames.
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    /// Reset the timing statistics.
+    pub fn reset_stats(&mut self) {
+        self.stats = Stats::default();
+    }
+
+    /// Whether `color` is already shown by the LED, so there is nothing to transmit.
+    fn is_shown(&self, color: &RGB) -> bool {
+        !self.stale && self.color == *color
+    }
+
+    /// Number of frames of a transition lasting `duration`.
+    fn transition_steps(duration: Duration) -> u64 {
+        (duration.as_millis() / TRANSITION_STEP).max(1)
+    }
 }
 
 impl<'ch> LEDAdapter<'ch, Blocking> {
-    /// Set the color of the LED. In case an RMT transmission error happens, a warning log message
-    /// is emitted.
-    pub fn set_color(&mut self, color: &RGB) {
-        color.to_pulses(&mut self.buffer);
-        defmt::debug!("Setting LED color to: {:?}", color);
-        defmt::trace!("Transmitting: {=[?; 25]}", self.buffer);
-
-        let ch = defmt::expect!(
+    /// Set the color of the LED, `color` can be an [`RGB`] or an [`HSV`] color. In case an RMT
+    /// transmission error happens, a warning log message is emitted.
+    ///
+    /// If a transition duration is set, this function blocks until the transition ends. Nothing is
+    /// transmitted if the LED already shows `color`, see [`LEDAdapter::force_color`].
+    pub fn set_color(&mut self, color: impl Into<RGB>) {
+        if let Err(e) = self.set_color_checked(color) {
+            warn!("LED color not set: {:?}", e);
+        }
+    }
+
+    /// Set the color of the LED like [`LEDAdapter::set_color`], but return the RMT transmission
+    /// error instead of logging it.
+    ///
+    /// A transition is aborted on the first error, the LED keeps the last intermediate color.
+    pub fn set_color_checked(&mut self, color: impl Into<RGB>) -> Result<(), Error> {
+        let color: &RGB = &color.into();
+        if self.is_shown(color) {
+            return Ok(());
+        }
+        if self.transition.as_ticks() == 0 {
+            return self.try_transmit(color);
+        }
+
+        let from = self.color;
+        let steps = Self::transition_steps(self.transition);
+        let delay = Delay::new();
+        for step in 1..=steps {
+            delay.delay_millis(TRANSITION_STEP as u32);
+            self.try_transmit(&from.blend(color, step, steps))?;
+        }
+        Ok(())
+    }
+
+    /// Set the color of the LED like [`LEDAdapter::set_color`], but transmit it even if the LED
+    /// already shows it, e.g. to recover an LED which glitched on a noisy line.
+    pub fn force_color(&mut self, color: impl Into<RGB>) {
+        self.stale = true;
+        self.set_color(color);
+    }
+
+    /// Transmit `color` to the LED.
+    fn try_transmit(&mut self, color: &RGB) -> Result<(), Error> {
+        self.color = *color;
+        // until the transmission succeeds, it may also be cancelled in the async adapter
+        self.stale = true;
+        let start = Instant::now();
+        let corrected = self.correction.apply(&self.gamma.apply(color));
+        corrected.to_pulses(&mut self.buffer, &self.timing, self.order);
+        let render = start.elapsed();
+        debug!("Setting LED color to: {:?}", color);
+        trace!("Transmitting: {:?}", self.buffer);
+
+        let ch = expect!(
             self.channel.take(),
             "At this point `self.channel` should be `Some`"
         );
 
-        match ch.transmit(&self.buffer) {
-            Ok(tx) => match tx.wait() {
-                Ok(ch) => self.channel = Some(ch),
-                Err((e, ch)) => {
-                    defmt::warn!("LED color not set: {}", e);
-                    self.channel = Some(ch);
-                }
-            },
+        if let Some(left) = self.timing.latch_left(self.last_end) {
+            Delay::new().delay_micros(left.as_micros() as u32);
+        }
+        let start = Instant::now();
+        let result = ch.transmit(&self.buffer).map(|tx| tx.wait());
+        self.last_end = Some(Instant::now());
+        match result {
+            Ok(Ok(ch)) => {
+                self.stats.record(render, start.elapsed());
+                self.last_frame = Some(Frame::capture(color, &self.buffer, &self.timing));
+                self.stale = false;
+                self.channel = Some(ch);
+                Ok(())
+            }
+            Ok(Err((e, ch))) => {
+                self.stats.errors = self.stats.errors.wrapping_add(1);
+                self.channel = Some(ch);
+                Err(e)
+            }
             Err(_) => {
-                defmt::unreachable!("`self.buffer` is always a valid input to `ch.transmit()`")
+                unreachable!("`self.buffer` is always a valid input to `ch.transmit()`")
             }
         }
     }
 }
 
 impl<'ch> LEDAdapter<'ch, Async> {
-    /// Set the color of the LED. In case an RMT transmission error happens, a warning log message
-    /// is emitted.
-    pub async fn set_color(&mut self, color: &RGB) {
-        color.to_pulses(&mut self.buffer);
-        defmt::debug!("Setting LED color to: {:?}", color);
-        defmt::trace!("Transmitting: {=[?; 25]}", self.buffer);
-
-        let ch = defmt::expect!(
+    /// Set the color of the LED, `color` can be an [`RGB`] or an [`HSV`] color. In case an RMT
+    /// transmission error happens, a warning log message is emitted.
+    ///
+    /// If a transition duration is set, this function completes when the transition ends. It is
+    /// safe to drop the future in the middle of a transition, the LED keeps the last intermediate
+    /// color.
+    ///
+    /// Nothing is transmitted if the LED already shows `color`, see [`LEDAdapter::force_color`].
+    pub async fn set_color(&mut self, color: impl Into<RGB>) {
+        if let Err(e) = self.set_color_checked(color).await {
+            warn!("LED color not set: {:?}", e);
+        }
+    }
+
+    /// Set the color of the LED like [`LEDAdapter::set_color`], but return the RMT transmission
+    /// error instead of logging it.
+    ///
+    /// A transition is aborted on the first error, the LED keeps the last intermediate color.
+    pub async fn set_color_checked(&mut self, color: impl Into<RGB>) -> Result<(), Error> {
+        let color: &RGB = &color.into();
+        if self.is_shown(color) {
+            return Ok(());
+        }
+        if self.transition.as_ticks() == 0 {
+            return self.try_transmit(color).await;
+        }
+
+        self.fade(color, self.transition).await
+    }
+
+    /// Set the color of the LED like [`LEDAdapter::set_color`], but transmit it even if the LED
+    /// already shows it, e.g. to recover an LED which glitched on a noisy line.
+    pub async fn force_color(&mut self, color: impl Into<RGB>) {
+        self.stale = true;
+        self.set_color(color).await;
+    }
+
+    /// Cross-fade from the current color to `color` over `duration`, stopping on the first error.
+    async fn fade(&mut self, color: &RGB, duration: Duration) -> Result<(), Error> {
+        let from = self.color;
+        let steps = Self::transition_steps(duration);
+        let mut ticker = Ticker::every(Duration::from_millis(TRANSITION_STEP));
+        for step in 1..=steps {
+            ticker.next().await;
+            self.try_transmit(&from.blend(color, step, steps)).await?;
+        }
+        Ok(())
+    }
+
+    /// Transmit `color` to the LED, logging the errors.
+    async fn transmit(&mut self, color: &RGB) {
+        if let Err(e) = self.try_transmit(color).await {
+            warn!("LED color not set: {:?}", e);
+        }
+    }
+
+    /// Transmit the 16-bit `color` to the LED, dithered to 8 bits by `dither`, logging the errors.
+    pub(crate) async fn transmit_dithered(&mut self, color: &RGB16, dither: &mut Dither) {
+        let output = dither.frame(&self.correction.apply16(&self.gamma.apply16(color)));
+        if let Err(e) = self.try_send(&color.to_rgb(), &output).await {
+            warn!("LED color not set: {:?}", e);
+        }
+        // the LED shows a single frame of the dithering, not `self.color`
+        self.stale = true;
+    }
+
+    /// Transmit `color` to the LED.
+    async fn try_transmit(&mut self, color: &RGB) -> Result<(), Error> {
+        let output = self.correction.apply(&self.gamma.apply(color));
+        self.try_send(color, &output).await
+    }
+
+    /// Transmit `output`, the corrected `color`, to the LED.
+    async fn try_send(&mut self, color: &RGB, output: &RGB) -> Result<(), Error> {
+        self.color = *color;
+        // until the transmission succeeds, it may also be cancelled in the async adapter
+        self.stale = true;
+        let start = Instant::now();
+        output.to_pulses(&mut self.buffer, &self.timing, self.order);
+        let render = start.elapsed();
+        debug!("Setting LED color to: {:?}", color);
+        trace!("Transmitting: {:?}", self.buffer);
+
+        let ch = expect!(
             self.channel.as_mut(),
             "We never leave this value as `None` in the async adapter"
         );
 
-        if let Err(e) = ch.transmit(&self.buffer).await {
-            defmt::warn!("LED color not set: {}", e);
+        if let Some(left) = self.timing.latch_left(self.last_end) {
+            Timer::after(left).await;
+        }
+        let start = Instant::now();
+        let result = ch.transmit(&self.buffer).await;
+        self.last_end = Some(Instant::now());
+        match result {
+            Ok(()) => {
+                self.stats.record(render, start.elapsed());
+                self.last_frame = Some(Frame::capture(color, &self.buffer, &self.timing));
+                self.stale = false;
+                Ok(())
+            }
+            Err(e) => {
+                self.stats.errors = self.stats.errors.wrapping_add(1);
+                Err(e)
+            }
         }
     }
 }
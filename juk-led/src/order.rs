@@ -0,0 +1,50 @@
+//! The [`ColorOrder`] of the bytes sent to the LEDs.
+
+use crate::RGB;
+
+/// The order in which the color bytes are sent to a single-wire LED chip.
+///
+/// The WS2812B and most of its relatives expect GRB, while some clones expect RGB or BRG. A wrong
+/// order shows up as swapped colors, e.g. red displayed as green.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ColorOrder {
+    #[default]
+    Grb,
+    Rgb,
+    Brg,
+    Rbg,
+    Gbr,
+    Bgr,
+}
+
+impl ColorOrder {
+    /// Parse a color order by name, e.g. `grb`. The lookup is case insensitive.
+    pub fn from_name(name: &str) -> Option<Self> {
+        [
+            ("grb", Self::Grb),
+            ("rgb", Self::Rgb),
+            ("brg", Self::Brg),
+            ("rbg", Self::Rbg),
+            ("gbr", Self::Gbr),
+            ("bgr", Self::Bgr),
+        ]
+        .into_iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|(_, order)| order)
+    }
+
+    /// The bytes of `color` in transmission order.
+    pub(crate) fn bytes(&self, color: &RGB) -> [u8; 3] {
+        let RGB { r, g, b } = *color;
+        match self {
+            Self::Grb => [g, r, b],
+            Self::Rgb => [r, g, b],
+            Self::Brg => [b, r, g],
+            Self::Rbg => [r, b, g],
+            Self::Gbr => [g, b, r],
+            Self::Bgr => [b, g, r],
+        }
+    }
+}
@@ -0,0 +1,139 @@
+//! Parsing of [`RGB`] colors from text, shared by all the places accepting a color from the user.
+
+use core::fmt;
+
+use crate::{HSV, RGB, colors};
+
+/// An error returned by [`parse_color`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ColorParseError {
+    /// The input does not match any of the accepted formats.
+    Syntax,
+    /// A hex color does not have 3 or 6 digits, or contains a non-hex digit.
+    InvalidHex,
+    /// A function (`rgb(...)` or `hsv(...)`) does not have exactly 3 numeric arguments.
+    InvalidArguments,
+    /// A component is out of its range.
+    OutOfRange,
+    /// The color name is not known.
+    UnknownName,
+}
+
+impl fmt::Display for ColorParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Syntax => "invalid color syntax",
+            Self::InvalidHex => "a hex color needs 3 or 6 hex digits",
+            Self::InvalidArguments => "expected 3 numeric arguments",
+            Self::OutOfRange => "color component out of range",
+            Self::UnknownName => "unknown color name",
+        })
+    }
+}
+
+/// Parse a color from `text`.
+///
+/// The accepted formats are:
+/// - hex: `#ff8000`, `0xff8000` or the short form `#f80`, the prefix is required so that a
+///   name like `bad` is never read as a hex color
+/// - `rgb(r, g, b)`: components in the range 0-255
+/// - `hsv(h, s, v)`: hue in degrees (0-360), saturation and value in percent (0-100)
+/// - a color name, e.g. `red` or `off`, see [`colors`]
+///
+/// Whitespace around the input and the function arguments is ignored, the parsing is case
+/// insensitive.
+pub fn parse_color(text: &str) -> Result<RGB, ColorParseError> {
+    let text = text.trim();
+
+    let hex = text
+        .strip_prefix('#')
+        .or_else(|| text.strip_prefix("0x"))
+        .or_else(|| text.strip_prefix("0X"));
+    if let Some(hex) = hex {
+        return parse_hex(hex);
+    }
+    if let Some(args) = function_args(text, "rgb") {
+        let [r, g, b] = parse_args(args, [255, 255, 255])?;
+        return Ok(RGB::new(r as u8, g as u8, b as u8));
+    }
+    if let Some(args) = function_args(text, "hsv") {
+        let [h, s, v] = parse_args(args, [360, 100, 100])?;
+        // saturation and value are given in percent
+        let percent = |x: u16| (x as u32 * 255 / 100) as u8;
+        return Ok(HSV::new(h, percent(s), percent(v)).to_rgb());
+    }
+
+    if let Some(color) = colors::from_name(text) {
+        return Ok(color);
+    }
+
+    if text.bytes().all(|b| b.is_ascii_alphabetic()) {
+        Err(ColorParseError::UnknownName)
+    } else {
+        Err(ColorParseError::Syntax)
+    }
+}
+
+impl RGB {
+    /// Parse a hex color, e.g. `#ff00aa`, `ff00aa` or the short form `#f0a`.
+    ///
+    /// Unlike [`parse_color`], only the hex format is accepted. Whitespace around the input is
+    /// ignored.
+    pub fn from_hex(text: &str) -> Result<RGB, ColorParseError> {
+        let text = text.trim();
+        parse_hex(text.strip_prefix('#').unwrap_or(text))
+    }
+}
+
+/// Parse a 3 or 6 digit hex color.
+fn parse_hex(hex: &str) -> Result<RGB, ColorParseError> {
+    if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(ColorParseError::InvalidHex);
+    }
+
+    let digit = |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).unwrap_or(0);
+    match hex.len() {
+        // each digit is repeated, #f80 is #ff8800
+        3 => Ok(RGB::new(digit(0) * 0x11, digit(1) * 0x11, digit(2) * 0x11)),
+        6 => {
+            let byte = |i: usize| (digit(i) << 4) | digit(i + 1);
+            Ok(RGB::new(byte(0), byte(2), byte(4)))
+        }
+        _ => Err(ColorParseError::InvalidHex),
+    }
+}
+
+/// Extract the arguments of a `name(...)` function call, the name is case insensitive.
+fn function_args<'a>(text: &'a str, name: &str) -> Option<&'a str> {
+    let prefix = text.get(..name.len())?;
+    if !prefix.eq_ignore_ascii_case(name) {
+        return None;
+    }
+    text[name.len()..]
+        .trim_start()
+        .strip_prefix('(')?
+        .strip_suffix(')')
+}
+
+/// Parse exactly 3 comma separated arguments, each not greater than its value in `max`.
+fn parse_args(args: &str, max: [u16; 3]) -> Result<[u16; 3], ColorParseError> {
+    let mut values = [0; 3];
+    let mut args = args.split(',');
+
+    for (value, max) in values.iter_mut().zip(max) {
+        let arg = args.next().ok_or(ColorParseError::InvalidArguments)?;
+        *value = arg
+            .trim()
+            .parse()
+            .map_err(|_| ColorParseError::InvalidArguments)?;
+        if *value > max {
+            return Err(ColorParseError::OutOfRange);
+        }
+    }
+
+    match args.next() {
+        Some(_) => Err(ColorParseError::InvalidArguments),
+        None => Ok(values),
+    }
+}
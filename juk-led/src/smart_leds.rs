@@ -0,0 +1,159 @@
+//! The [`SmartLedsWrite`] and [`SmartLedsWriteAsync`] implementations of the adapters, enabled by
+//! the `smart-leds` feature.
+//!
+//! They give all the adapters a common, driver-agnostic interface, which also lets the effect
+//! crates of the smart-leds ecosystem drive them directly:
+//!
+//! ```
+//! use smart_leds::{SmartLedsWrite, brightness, colors};
+//!
+//! let frame = [colors::RED, colors::ORANGE, colors::YELLOW, colors::GREEN];
+//! strip.write(brightness(frame.into_iter(), 32)).unwrap();
+//! ```
+//!
+//! The colors are written right away, ignoring the transition duration of [`LEDAdapter`], as the
+//! effects generate their own frames. Unlike the rest of the crate, the writes return the
+//! transmission errors instead of logging them, so the effects can handle them.
+
+use esp_hal::{Async, Blocking, DriverMode, rmt, spi};
+use smart_leds_trait::{RGB8, SmartLedsWrite, SmartLedsWriteAsync};
+
+use crate::{Apa102Adapter, LEDAdapter, RGB, StripAdapter};
+
+impl From<RGB8> for RGB {
+    fn from(color: RGB8) -> Self {
+        RGB::new(color.r, color.g, color.b)
+    }
+}
+
+impl From<RGB> for RGB8 {
+    fn from(color: RGB) -> Self {
+        RGB8::new(color.r, color.g, color.b)
+    }
+}
+
+/// Writes the first color of the iterator, the rest is ignored.
+impl<'ch> SmartLedsWrite for LEDAdapter<'ch, Blocking> {
+    type Error = rmt::Error;
+    type Color = RGB8;
+
+    fn write<T, I>(&mut self, iterator: T) -> Result<(), Self::Error>
+    where
+        T: IntoIterator<Item = I>,
+        I: Into<Self::Color>,
+    {
+        match iterator.into_iter().next() {
+            Some(color) => self.try_transmit(&color.into().into()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Writes the first color of the iterator, the rest is ignored.
+impl<'ch> SmartLedsWriteAsync for LEDAdapter<'ch, Async> {
+    type Error = rmt::Error;
+    type Color = RGB8;
+
+    async fn write<T, I>(&mut self, iterator: T) -> Result<(), Self::Error>
+    where
+        T: IntoIterator<Item = I>,
+        I: Into<Self::Color>,
+    {
+        match iterator.into_iter().next() {
+            Some(color) => self.try_transmit(&color.into().into()).await,
+            None => Ok(()),
+        }
+    }
+}
+
+impl<'ch, Dm, const N: usize> StripAdapter<'ch, Dm, N>
+where
+    Dm: DriverMode,
+{
+    /// Fill the back buffer from `iterator`, the pixels past its end keep their colors.
+    fn load<T, I>(&mut self, iterator: T)
+    where
+        T: IntoIterator<Item = I>,
+        I: Into<RGB8>,
+    {
+        for (index, color) in iterator.into_iter().take(N).enumerate() {
+            self.set_pixel(index, &color.into().into());
+        }
+    }
+}
+
+/// Writes the colors to the back buffer and commits it, the colors past `N` are ignored.
+impl<'ch, const N: usize> SmartLedsWrite for StripAdapter<'ch, Blocking, N> {
+    type Error = rmt::Error;
+    type Color = RGB8;
+
+    fn write<T, I>(&mut self, iterator: T) -> Result<(), Self::Error>
+    where
+        T: IntoIterator<Item = I>,
+        I: Into<Self::Color>,
+    {
+        self.load(iterator);
+        self.swap_and_show_checked()
+    }
+}
+
+/// Writes the colors to the back buffer and commits it, the colors past `N` are ignored.
+impl<'ch, const N: usize> SmartLedsWriteAsync for StripAdapter<'ch, Async, N> {
+    type Error = rmt::Error;
+    type Color = RGB8;
+
+    async fn write<T, I>(&mut self, iterator: T) -> Result<(), Self::Error>
+    where
+        T: IntoIterator<Item = I>,
+        I: Into<Self::Color>,
+    {
+        self.load(iterator);
+        self.swap_and_show_checked().await
+    }
+}
+
+impl<'d, Dm, const N: usize> Apa102Adapter<'d, Dm, N>
+where
+    Dm: DriverMode,
+{
+    /// Fill the frame buffer from `iterator`, the pixels past its end keep their colors.
+    fn load<T, I>(&mut self, iterator: T)
+    where
+        T: IntoIterator<Item = I>,
+        I: Into<RGB8>,
+    {
+        for (index, color) in iterator.into_iter().take(N).enumerate() {
+            self.set_pixel(index, &color.into().into());
+        }
+    }
+}
+
+/// Writes the colors to the frame buffer and shows it, the colors past `N` are ignored.
+impl<'d, const N: usize> SmartLedsWrite for Apa102Adapter<'d, Blocking, N> {
+    type Error = spi::Error;
+    type Color = RGB8;
+
+    fn write<T, I>(&mut self, iterator: T) -> Result<(), Self::Error>
+    where
+        T: IntoIterator<Item = I>,
+        I: Into<Self::Color>,
+    {
+        self.load(iterator);
+        self.show_checked()
+    }
+}
+
+/// Writes the colors to the frame buffer and shows it, the colors past `N` are ignored.
+impl<'d, const N: usize> SmartLedsWriteAsync for Apa102Adapter<'d, Async, N> {
+    type Error = spi::Error;
+    type Color = RGB8;
+
+    async fn write<T, I>(&mut self, iterator: T) -> Result<(), Self::Error>
+    where
+        T: IntoIterator<Item = I>,
+        I: Into<Self::Color>,
+    {
+        self.load(iterator);
+        self.show_checked().await
+    }
+}
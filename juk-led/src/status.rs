@@ -0,0 +1,116 @@
+//! The [`StatusLed`] showing the state of the firmware with an [`LEDAdapter`].
+//!
+//! The states are defined by the firmware, every state maps to a [`Pattern`]:
+//!
+//! ```
+//! use juk_led::{LEDAdapter, Pattern, RGB, Status, StatusLed};
+//!
+//! #[derive(Clone, Copy)]
+//! enum State {
+//!     Booting,
+//!     Idle,
+//!     BinaryMode,
+//!     Error,
+//! }
+//!
+//! impl Status for State {
+//!     fn pattern(&self) -> Pattern {
+//!         match self {
+//!             State::Booting => Pattern::Breathe(RGB::new(0x00, 0x00, 0xff)),
+//!             State::Idle => Pattern::Solid(RGB::new(0x00, 0x20, 0x00)),
+//!             State::BinaryMode => Pattern::Solid(RGB::new(0x00, 0x20, 0x20)),
+//!             State::Error => {
+//!                 Pattern::Blink(RGB::new(0xff, 0x00, 0x00), Duration::from_millis(500))
+//!             }
+//!         }
+//!     }
+//! }
+//!
+//! static STATUS: StatusLed<State> = StatusLed::new();
+//!
+//! #[embassy_executor::task]
+//! async fn status_task(mut led: LEDAdapter<'static, Async>) -> ! {
+//!     STATUS.run(&mut led).await
+//! }
+//!
+//! // anywhere in the firmware
+//! STATUS.set(State::Error);
+//! ```
+
+use embassy_futures::select::{Either, select};
+use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, signal::Signal};
+use embassy_time::Duration;
+use esp_hal::Async;
+
+use crate::{LEDAdapter, RGB, animation};
+
+/// The way a state is shown by the LED.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Pattern {
+    /// The LED is black.
+    Off,
+    /// The LED shows the color, honoring the transition duration of the adapter.
+    Solid(RGB),
+    /// The LED blinks in the color with the period, see [`animation::blink`].
+    Blink(RGB, Duration),
+    /// The LED breathes in the color, see [`animation::breathe`].
+    Breathe(RGB),
+}
+
+/// A state shown by the [`StatusLed`], usually an enum.
+pub trait Status: Copy {
+    /// The pattern showing the state.
+    fn pattern(&self) -> Pattern;
+}
+
+/// Shows the state of the firmware, set from any task with [`StatusLed::set`].
+///
+/// Nothing is shown until the first state is set.
+pub struct StatusLed<S> {
+    state: Signal<CriticalSectionRawMutex, S>,
+}
+
+impl<S: Status> StatusLed<S> {
+    /// Constructor for the [`StatusLed`] struct, usable in a `static`.
+    pub const fn new() -> Self {
+        Self {
+            state: Signal::new(),
+        }
+    }
+
+    /// Show `state`, replacing the pattern of the previous state.
+    pub fn set(&self, state: S) {
+        self.state.signal(state);
+    }
+
+    /// Drive `led`, showing the pattern of the last set state.
+    ///
+    /// Meant to be awaited by a dedicated task, which owns the LED.
+    pub async fn run(&self, led: &mut LEDAdapter<'_, Async>) -> ! {
+        let mut state = self.state.wait().await;
+        loop {
+            match select(show(led, state.pattern()), self.state.wait()).await {
+                Either::First(never) => never,
+                Either::Second(next) => state = next,
+            }
+        }
+    }
+}
+
+impl<S: Status> Default for StatusLed<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Show `pattern` with `led` until the future is dropped.
+async fn show(led: &mut LEDAdapter<'_, Async>, pattern: Pattern) -> ! {
+    match pattern {
+        Pattern::Off => led.set_color(RGB::new(0x00, 0x00, 0x00)).await,
+        Pattern::Solid(color) => led.set_color(color).await,
+        Pattern::Blink(color, period) => animation::blink(led, color, period).await,
+        Pattern::Breathe(color) => animation::breathe(led, color).await,
+    }
+    core::future::pending().await
+}
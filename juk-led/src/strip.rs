@@ -0,0 +1,477 @@
+//! The [`StripAdapter`] driving a chain of single-wire RGB LEDs.
+
+use embassy_time::{Duration, Instant, Timer};
+use esp_hal::{
+    Async,
+    Blocking,
+    DriverMode,
+    delay::Delay,
+    gpio::interconnect::PeripheralOutput,
+    rmt::{Channel, Error, PulseCode, Tx, TxChannelCreator},
+};
+
+use crate::{ColorCorrection, ColorOrder, Gamma, LEDAdapter, LedTiming, RGB};
+
+/// The current drawn by a single color channel of a WS2812B at the full brightness, in mA.
+const WS2812B_CHANNEL_CURRENT: u32 = 20;
+
+/// Number of [`PulseCode`]s needed to transmit a chain of `pixels` LEDs, including the end marker.
+///
+/// Use it to size the buffer passed to [`StripAdapter::new`].
+pub const fn pulses_len(pixels: usize) -> usize {
+    pixels * 24 + 1
+}
+
+/// A driver for a chain of `N` single-wire RGB LEDs, WS2812B by default or any other chip
+/// described by a [`LedTiming`].
+///
+/// The adapter is double buffered. The colors are set in the back buffer with
+/// [`StripAdapter::set_pixel`] and [`StripAdapter::fill`], and committed to the front buffer and
+/// transmitted to the whole chain at once with [`StripAdapter::swap_and_show`]. A half-edited back
+/// buffer is never transmitted, e.g. by an animation retransmitting the front buffer with
+/// [`StripAdapter::show`] while a command is still setting the pixels. Just like [`LEDAdapter`],
+/// the driver works in synchronous and asynchronous modes and handles the errors by emiting a
+/// warning message, [`StripAdapter::show_checked`] returns them instead.
+///
+/// The frame does not have to fit in the RMT channel RAM: the HAL streams it from the
+/// [`PulseCode`] buffer, refilling the channel RAM in wrap mode whenever half of it has been
+/// sent, so arbitrarily long strips can be driven. The buffer is provided by the caller, as its
+/// length depends on `N`:
+///
+/// ```
+/// use esp_hal::rmt::PulseCode;
+/// use juk_led::{RGB, StripAdapter, pulses_len};
+///
+/// let mut pulses = [PulseCode::end_marker(); pulses_len(8)];
+/// let mut strip = StripAdapter::<_, 8>::new(rmt.channel0, peripherals.GPIO38, &mut pulses);
+/// strip.fill(&RGB::new(0x00, 0x00, 0x20));
+/// strip.set_pixel(0, &RGB::new(0xff, 0x00, 0x00));
+/// strip.swap_and_show();
+/// ```
+pub struct StripAdapter<'ch, Dm, const N: usize>
+where
+    Dm: DriverMode,
+{
+    channel: Option<Channel<'ch, Dm, Tx>>,
+    pulses: &'ch mut [PulseCode],
+    /// The back buffer, edited by the user.
+    pixels: [RGB; N],
+    /// The brightness of the pixels in the back buffer, `255` is the full brightness.
+    brightness: [u8; N],
+    /// The front buffer, the frame last committed with [`StripAdapter::swap_and_show`].
+    front: [RGB; N],
+    gamma: Gamma,
+    correction: ColorCorrection,
+    timing: LedTiming,
+    order: ColorOrder,
+    /// The current of a color channel at the full brightness, in mA.
+    channel_current: u32,
+    /// The current the frames are scaled down to, in mA.
+    power_limit: Option<u32>,
+    /// The shortest time between the starts of two frames.
+    min_interval: Duration,
+    /// The start of the last transmission.
+    last_start: Option<Instant>,
+    /// The end of the last transmission, see [`LedTiming::reset`].
+    last_end: Option<Instant>,
+}
+
+impl<'ch, Dm, const N: usize> StripAdapter<'ch, Dm, N>
+where
+    Dm: DriverMode,
+{
+    /// Construct a new [`StripAdapter`] from an RMT channel, an output pin and a buffer for the
+    /// pulse codes. All the pixels are initially black.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `pulses` is shorter than [`pulses_len(N)`](pulses_len) or if
+    /// it fails to configure the RMT channel.
+    pub fn new<C, O>(channel: C, pin: O, pulses: &'ch mut [PulseCode]) -> Self
+    where
+        C: TxChannelCreator<'ch, Dm>,
+        O: PeripheralOutput<'ch>,
+    {
+        Self::new_with_memory_blocks(channel, pin, pulses, 1)
+    }
+
+    /// Construct a new [`StripAdapter`] like [`StripAdapter::new`], allocating `blocks` blocks of
+    /// the RMT channel RAM ([`CHANNEL_RAM_SIZE`](esp_hal::rmt::CHANNEL_RAM_SIZE) pulse codes each)
+    /// to the channel.
+    ///
+    /// While a frame is streamed, the refill has to happen before the channel sends the other
+    /// half of its RAM. A single block covers one LED per half, which the asynchronous adapter
+    /// can miss when the executor is busy, showing as glitches at the end of long strips. More
+    /// blocks give the refill more time, but the channels following this one cannot use the
+    /// borrowed blocks.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `pulses` is shorter than [`pulses_len(N)`](pulses_len) or if
+    /// it fails to configure the RMT channel, e.g. when the blocks are used by another channel.
+    pub fn new_with_memory_blocks<C, O>(
+        channel: C,
+        pin: O,
+        pulses: &'ch mut [PulseCode],
+        blocks: u8,
+    ) -> Self
+    where
+        C: TxChannelCreator<'ch, Dm>,
+        O: PeripheralOutput<'ch>,
+    {
+        assert!(
+            pulses.len() >= pulses_len(N),
+            "The pulse buffer of a strip of {} LEDs needs {} entries",
+            N,
+            pulses_len(N)
+        );
+        let channel = expect!(
+            channel.configure_tx(
+                pin,
+                LEDAdapter::<'ch, Dm>::channel_config().with_memsize(blocks),
+            ),
+            "Failed to configure the RMT channel"
+        );
+
+        Self {
+            channel: Some(channel),
+            pulses,
+            pixels: [RGB::new(0x00, 0x00, 0x00); N],
+            brightness: [u8::MAX; N],
+            front: [RGB::new(0x00, 0x00, 0x00); N],
+            gamma: Gamma::Linear,
+            correction: ColorCorrection::NONE,
+            timing: LedTiming::WS2812B,
+            order: ColorOrder::Grb,
+            channel_current: WS2812B_CHANNEL_CURRENT,
+            power_limit: None,
+            min_interval: Duration::from_ticks(0),
+            last_start: None,
+            last_end: None,
+        }
+    }
+
+    /// Set the gamma correction applied to the transmitted colors.
+    pub fn with_gamma(mut self, gamma: Gamma) -> Self {
+        self.gamma = gamma;
+        self
+    }
+
+    /// Set the gamma correction applied to the transmitted colors.
+    pub fn set_gamma(&mut self, gamma: Gamma) {
+        self.gamma = gamma;
+    }
+
+    /// Set the white point correction of the LED chips, applied after the gamma correction.
+    pub fn with_correction(mut self, correction: ColorCorrection) -> Self {
+        self.correction = correction;
+        self
+    }
+
+    /// Set the white point correction of the LED chips, applied after the gamma correction.
+    pub fn set_correction(&mut self, correction: ColorCorrection) {
+        self.correction = correction;
+    }
+
+    /// Set the bit timing of the LED chips, see the [`LedTiming`] presets.
+    pub fn with_timing(mut self, timing: LedTiming) -> Self {
+        self.timing = timing;
+        self
+    }
+
+    /// Set the bit timing of the LED chips, see the [`LedTiming`] presets.
+    ///
+    /// The frames of the presets are rendered from a table precomputed at compile time, custom
+    /// timings are rendered bit by bit.
+    pub fn set_timing(&mut self, timing: LedTiming) {
+        self.timing = timing;
+    }
+
+    /// Set the order of the color bytes expected by the LED chips.
+    pub fn with_color_order(mut self, order: ColorOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Set the order of the color bytes expected by the LED chips.
+    pub fn set_color_order(&mut self, order: ColorOrder) {
+        self.order = order;
+    }
+
+    /// Set the current drawn by a single color channel of the LED chips at the full brightness,
+    /// in mA, used to estimate the current of a frame. The default of 20 mA fits the WS2812B.
+    pub fn with_channel_current_ma(mut self, current: u32) -> Self {
+        self.channel_current = current;
+        self
+    }
+
+    /// Set the current drawn by a single color channel of the LED chips at the full brightness,
+    /// see [`StripAdapter::with_channel_current_ma`].
+    pub fn set_channel_current_ma(&mut self, current: u32) {
+        self.channel_current = current;
+    }
+
+    /// Limit the estimated current of the strip to `limit` mA, `None` removes the limit (the
+    /// default).
+    ///
+    /// The frames estimated to draw more are scaled down as a whole before the transmission, the
+    /// buffers keep the original colors. The estimate is made from the colors after the gamma and
+    /// white point corrections and does not include the quiescent current of the chips, so leave
+    /// some headroom.
+    pub fn with_power_limit_ma(mut self, limit: Option<u32>) -> Self {
+        self.power_limit = limit;
+        self
+    }
+
+    /// Limit the estimated current of the strip, see [`StripAdapter::with_power_limit_ma`].
+    pub fn set_power_limit_ma(&mut self, limit: Option<u32>) {
+        self.power_limit = limit;
+    }
+
+    /// Limit the frame rate, a frame shown sooner than `interval` after the start of the previous
+    /// one waits for the interval to pass.
+    ///
+    /// Keeps callers showing frames in a fast loop from exceeding the refresh rate of the strip or
+    /// starving the other users of the RMT peripheral. There is no limit by default.
+    pub fn with_min_frame_interval(mut self, interval: Duration) -> Self {
+        self.min_interval = interval;
+        self
+    }
+
+    /// Limit the frame rate, see [`StripAdapter::with_min_frame_interval`].
+    pub fn set_min_frame_interval(&mut self, interval: Duration) {
+        self.min_interval = interval;
+    }
+
+    /// Set the color of the pixel at `index` in the back buffer, the first pixel is the one
+    /// closest to the data pin.
+    ///
+    /// An out of range `index` is ignored with a warning.
+    pub fn set_pixel(&mut self, index: usize, color: &RGB) {
+        match self.pixels.get_mut(index) {
+            Some(pixel) => *pixel = *color,
+            None => warn!("Pixel {} out of range of a strip of {} LEDs", index, N),
+        }
+    }
+
+    /// Set the brightness of the pixel at `index` in the back buffer, from `0` (off) to `255` (full
+    /// brightness, the default).
+    ///
+    /// The brightness scales the color of the pixel when the back buffer is committed, so a pixel
+    /// can be dimmed without changing its color. It is applied before the gamma correction. An out
+    /// of range `index` is ignored with a warning.
+    pub fn set_pixel_brightness(&mut self, index: usize, brightness: u8) {
+        match self.brightness.get_mut(index) {
+            Some(level) => *level = brightness,
+            None => warn!("Pixel {} out of range of a strip of {} LEDs", index, N),
+        }
+    }
+
+    /// The brightness of the pixel at `index` in the back buffer.
+    pub fn pixel_brightness(&self, index: usize) -> Option<u8> {
+        self.brightness.get(index).copied()
+    }
+
+    /// Set all the pixels in the back buffer to `color`.
+    pub fn fill(&mut self, color: &RGB) {
+        self.pixels = [*color; N];
+    }
+
+    /// The color of the pixel at `index` in the back buffer.
+    pub fn pixel(&self, index: usize) -> Option<RGB> {
+        self.pixels.get(index).copied()
+    }
+
+    /// The back buffer.
+    pub fn pixels(&self) -> &[RGB; N] {
+        &self.pixels
+    }
+
+    /// The front buffer, i.e. the frame displayed by the strip, with the brightness of the pixels
+    /// applied.
+    pub fn front(&self) -> &[RGB; N] {
+        &self.front
+    }
+
+    /// The time left until the next frame can be transmitted, `None` if it can be transmitted
+    /// right away.
+    fn wait_left(&self) -> Option<Duration> {
+        let latch = self.timing.latch_left(self.last_end);
+        let now = Instant::now();
+        let interval = self
+            .last_start
+            .map(|start| start + self.min_interval)
+            .filter(|next| *next > now)
+            .map(|next| next - now);
+        latch.max(interval)
+    }
+
+    /// Copy the back buffer to the front buffer, applying the brightness of the pixels.
+    fn commit(&mut self) {
+        let black = RGB::new(0x00, 0x00, 0x00);
+        let pixels = self.pixels.iter().zip(self.brightness);
+        for (front, (pixel, brightness)) in self.front.iter_mut().zip(pixels) {
+            *front = black.lerp(*pixel, brightness);
+        }
+    }
+
+    /// The brightness the front buffer is scaled to, `255` unless its estimated current exceeds
+    /// the power limit.
+    fn power_scale(&self) -> u8 {
+        let limit = match self.power_limit {
+            Some(limit) => limit as u64,
+            None => return u8::MAX,
+        };
+        let total: u64 = self
+            .front
+            .iter()
+            .map(|pixel| {
+                let RGB { r, g, b } = self.correction.apply(&self.gamma.apply(pixel));
+                r as u64 + g as u64 + b as u64
+            })
+            .sum();
+        let current = total * self.channel_current as u64 / 255;
+        if current <= limit {
+            return u8::MAX;
+        }
+
+        let scale = (limit * 255 / current) as u8;
+        debug!(
+            "Frame of {} mA scaled to {}/255 to stay within {} mA",
+            current, scale, limit
+        );
+        scale
+    }
+
+    /// The color transmitted for `pixel`, corrected and scaled to the `scale` brightness.
+    fn output(gamma: Gamma, correction: ColorCorrection, scale: u8, pixel: &RGB) -> RGB {
+        RGB::new(0x00, 0x00, 0x00).lerp(correction.apply(&gamma.apply(pixel)), scale)
+    }
+
+    /// Convert the `N` colors of `output` to pulse codes with the bit `timing`. Returns the part
+    /// of `pulses` to transmit.
+    fn render<'a>(
+        output: impl Iterator<Item = RGB>,
+        timing: &LedTiming,
+        order: ColorOrder,
+        pulses: &'a mut [PulseCode],
+    ) -> &'a [PulseCode] {
+        for (color, pulses) in output.zip(pulses.chunks_exact_mut(24)) {
+            color.to_pulses(pulses, timing, order);
+        }
+        pulses[N * 24] = PulseCode::end_marker();
+        debug!("Showing a frame of {} LEDs", N);
+
+        &pulses[..pulses_len(N)]
+    }
+}
+
+impl<'ch, const N: usize> StripAdapter<'ch, Blocking, N> {
+    /// Commit the back buffer to the front buffer and transmit it to the strip.
+    ///
+    /// The back buffer keeps its contents, so the next frame can be edited incrementally.
+    pub fn swap_and_show(&mut self) {
+        self.commit();
+        self.show();
+    }
+
+    /// Commit the back buffer like [`StripAdapter::swap_and_show`], but return the RMT
+    /// transmission error instead of logging it.
+    pub fn swap_and_show_checked(&mut self) -> Result<(), Error> {
+        self.commit();
+        self.show_checked()
+    }
+
+    /// Transmit the front buffer to the strip, e.g. after changing the gamma correction. In case
+    /// an RMT transmission error happens, a warning log message is emitted.
+    pub fn show(&mut self) {
+        if let Err(e) = self.show_checked() {
+            warn!("LED strip frame not shown: {:?}", e);
+        }
+    }
+
+    /// Transmit the front buffer like [`StripAdapter::show`], but return the RMT transmission
+    /// error instead of logging it.
+    pub fn show_checked(&mut self) -> Result<(), Error> {
+        let wait = self.wait_left();
+        let ch = expect!(
+            self.channel.take(),
+            "At this point `self.channel` should be `Some`"
+        );
+        let (gamma, correction, scale) = (self.gamma, self.correction, self.power_scale());
+        let output = self
+            .front
+            .iter()
+            .map(|pixel| Self::output(gamma, correction, scale, pixel));
+        let pulses = Self::render(output, &self.timing, self.order, self.pulses);
+
+        if let Some(left) = wait {
+            Delay::new().delay_micros(left.as_micros() as u32);
+        }
+        self.last_start = Some(Instant::now());
+        let result = ch.transmit(pulses).map(|tx| tx.wait());
+        self.last_end = Some(Instant::now());
+        match result {
+            Ok(Ok(ch)) => {
+                self.channel = Some(ch);
+                Ok(())
+            }
+            Ok(Err((e, ch))) => {
+                self.channel = Some(ch);
+                Err(e)
+            }
+            Err(_) => {
+                unreachable!("The rendered frame is always a valid input to `ch.transmit()`")
+            }
+        }
+    }
+}
+
+impl<'ch, const N: usize> StripAdapter<'ch, Async, N> {
+    /// Commit the back buffer to the front buffer and transmit it to the strip.
+    ///
+    /// The back buffer keeps its contents, so the next frame can be edited incrementally.
+    pub async fn swap_and_show(&mut self) {
+        self.commit();
+        self.show().await;
+    }
+
+    /// Commit the back buffer like [`StripAdapter::swap_and_show`], but return the RMT
+    /// transmission error instead of logging it.
+    pub async fn swap_and_show_checked(&mut self) -> Result<(), Error> {
+        self.commit();
+        self.show_checked().await
+    }
+
+    /// Transmit the front buffer to the strip, e.g. after changing the gamma correction. In case
+    /// an RMT transmission error happens, a warning log message is emitted.
+    pub async fn show(&mut self) {
+        if let Err(e) = self.show_checked().await {
+            warn!("LED strip frame not shown: {:?}", e);
+        }
+    }
+
+    /// Transmit the front buffer like [`StripAdapter::show`], but return the RMT transmission
+    /// error instead of logging it.
+    pub async fn show_checked(&mut self) -> Result<(), Error> {
+        let wait = self.wait_left();
+        let (gamma, correction, scale) = (self.gamma, self.correction, self.power_scale());
+        let output = self
+            .front
+            .iter()
+            .map(|pixel| Self::output(gamma, correction, scale, pixel));
+        let pulses = Self::render(output, &self.timing, self.order, self.pulses);
+        let ch = expect!(
+            self.channel.as_mut(),
+            "We never leave this value as `None` in the async adapter"
+        );
+
+        if let Some(left) = wait {
+            Timer::after(left).await;
+        }
+        self.last_start = Some(Instant::now());
+        let result = ch.transmit(pulses).await;
+        self.last_end = Some(Instant::now());
+        result
+    }
+}
@@ -0,0 +1,161 @@
+//! The [`LedTiming`] bit timings of the supported LED chips.
+
+use embassy_time::{Duration, Instant};
+use esp_hal::{gpio::Level, rmt::PulseCode};
+
+/// The bit timings of a single-wire LED chip, in nanoseconds, and the reset latch between the
+/// frames, in microseconds.
+///
+/// The presets cover the common chips, other chips can be driven by constructing the timings from
+/// their datasheet with [`LedTiming::new`]. Only the bit timings change, the adapters always send
+/// 24-bit frames, with the bytes in the [`ColorOrder`](crate::ColorOrder) of the adapter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LedTiming {
+    /// High time of a `0` bit.
+    pub t0h: u32,
+    /// Low time of a `0` bit.
+    pub t0l: u32,
+    /// High time of a `1` bit.
+    pub t1h: u32,
+    /// Low time of a `1` bit.
+    pub t1l: u32,
+    /// The shortest low period between two frames, the chip latches the received color during
+    /// it.
+    ///
+    /// The adapters wait for it to pass before transmitting the next frame.
+    pub reset: u32,
+}
+
+impl LedTiming {
+    /// The WS2812B, the default.
+    pub const WS2812B: Self = Self::new(350, 800, 700, 600);
+
+    /// The WS2811 in the 800 kHz mode.
+    pub const WS2811: Self = Self::new(250, 1000, 600, 650);
+
+    /// The 12 V WS2815.
+    pub const WS2815: Self = Self::new(300, 900, 900, 300);
+
+    /// The TM1814.
+    ///
+    /// The TM1814 expects 32-bit RGBW frames preceded by a current setting, which the adapters do
+    /// not generate, so this preset only covers its bit timings.
+    pub const TM1814: Self = Self::new(360, 940, 960, 340);
+
+    /// Constructor for the [`LedTiming`] struct.
+    ///
+    /// The reset latch is set to 280 µs, required by the current revisions of the WS2812B and its
+    /// relatives. The older revisions only need 50 µs, see [`LedTiming::with_reset`].
+    pub const fn new(t0h: u32, t0l: u32, t1h: u32, t1l: u32) -> Self {
+        Self {
+            t0h,
+            t0l,
+            t1h,
+            t1l,
+            reset: 280,
+        }
+    }
+
+    /// Set the reset latch between the frames, in microseconds.
+    pub const fn with_reset(mut self, reset: u32) -> Self {
+        self.reset = reset;
+        self
+    }
+
+    /// The time left until the reset latch after a frame which ended at `end` is over, `None` if
+    /// it is over already or no frame was sent.
+    pub(crate) fn latch_left(&self, end: Option<Instant>) -> Option<Duration> {
+        let latched = end? + Duration::from_micros(self.reset as u64);
+        let now = Instant::now();
+        (latched > now).then(|| latched - now)
+    }
+
+    /// The pulse code of a `0` bit, calculated for an 80MHz peripheral clock.
+    pub(crate) const fn pulse_0(&self) -> PulseCode {
+        Self::pulse(self.t0h, self.t0l)
+    }
+
+    /// The pulse code of a `1` bit, calculated for an 80MHz peripheral clock.
+    pub(crate) const fn pulse_1(&self) -> PulseCode {
+        Self::pulse(self.t1h, self.t1l)
+    }
+
+    /// A pulse code high for `high` and then low for `low` nanoseconds.
+    const fn pulse(high: u32, low: u32) -> PulseCode {
+        PulseCode::new(
+            Level::High,
+            ((high * 80) / 1000) as u16,
+            Level::Low,
+            ((low * 80) / 1000) as u16,
+        )
+    }
+}
+
+impl Default for LedTiming {
+    fn default() -> Self {
+        Self::WS2812B
+    }
+}
+
+/// The [`PulseTable`] of [`LedTiming::WS2812B`].
+static WS2812B_TABLE: PulseTable = PulseTable::new(&LedTiming::WS2812B);
+
+/// The [`PulseTable`] of [`LedTiming::WS2811`].
+static WS2811_TABLE: PulseTable = PulseTable::new(&LedTiming::WS2811);
+
+/// The [`PulseTable`] of [`LedTiming::WS2815`].
+static WS2815_TABLE: PulseTable = PulseTable::new(&LedTiming::WS2815);
+
+/// The [`PulseTable`] of [`LedTiming::TM1814`].
+static TM1814_TABLE: PulseTable = PulseTable::new(&LedTiming::TM1814);
+
+/// The pulse codes of every byte value for a [`LedTiming`], so a frame is encoded by copying a
+/// row per byte instead of converting it bit by bit.
+///
+/// The table takes 8 KiB, so it is built at compile time for the presets only and shared by all
+/// the adapters, see [`PulseTable::preset`].
+pub(crate) struct PulseTable {
+    rows: [[PulseCode; 8]; 256],
+}
+
+impl PulseTable {
+    /// Precompute the pulse codes of every byte value sent with `timing`.
+    pub(crate) const fn new(timing: &LedTiming) -> Self {
+        let (pulse_0, pulse_1) = (timing.pulse_0(), timing.pulse_1());
+        let mut rows = [[pulse_0; 8]; 256];
+        let mut byte = 0;
+        while byte < 256 {
+            let mut pos = 0;
+            while pos < 8 {
+                if byte & (1 << pos) != 0 {
+                    rows[byte][pos] = pulse_1;
+                }
+                pos += 1;
+            }
+            byte += 1;
+        }
+        Self { rows }
+    }
+
+    /// The table of `timing` if it has the bit timings of one of the [`LedTiming`] presets, the
+    /// reset latch is not compared.
+    pub(crate) fn preset(timing: &LedTiming) -> Option<&'static Self> {
+        let bits = |t: &LedTiming| (t.t0h, t.t0l, t.t1h, t.t1l);
+        [
+            (LedTiming::WS2812B, &WS2812B_TABLE),
+            (LedTiming::WS2811, &WS2811_TABLE),
+            (LedTiming::WS2815, &WS2815_TABLE),
+            (LedTiming::TM1814, &TM1814_TABLE),
+        ]
+        .into_iter()
+        .find(|(preset, _)| bits(preset) == bits(timing))
+        .map(|(_, table)| table)
+    }
+
+    /// The pulse codes of `byte`, in transmission order.
+    pub(crate) fn row(&self, byte: u8) -> &[PulseCode; 8] {
+        &self.rows[byte as usize]
+    }
+}